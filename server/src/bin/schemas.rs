@@ -1,15 +1,119 @@
-use schemars::schema_for;
-use things_server::lists::{List, ListItem};
+//! Generates `../schemas/openapi.json` from [`things_server::registry::routes`]:
+//! each route's request/response types are walked into `components/schemas`
+//! and referenced from a `paths` entry via `$ref`, so the document always
+//! matches whatever routes `registry::router` actually serves.
 
-macro_rules! write_schema {
-    ($model:ty, $name:expr) => {{
-        let schema = schema_for!($model);
-        let output = serde_json::to_string_pretty(&schema).unwrap();
-        std::fs::write(format!("../schemas/{}.json", $name), output).unwrap();
-    }};
-}
+use schemars::schema::RootSchema;
+use serde_json::{json, Map, Value};
+use things_server::registry;
 
 fn main() {
-    write_schema!(ListItem, "item");
-    write_schema!(List, "list");
+    let mut schemas = Map::new();
+    let mut paths = Map::new();
+
+    for route in registry::routes() {
+        if let Some((name, schema)) = &route.request {
+            register(&mut schemas, name, schema);
+        }
+        if let Some((name, schema)) = &route.response {
+            register(&mut schemas, name, schema);
+        }
+
+        let mut operation = Map::new();
+        operation.insert("summary".to_owned(), json!(route.summary));
+        if let Some((name, _)) = &route.request {
+            operation.insert(
+                "requestBody".to_owned(),
+                json!({ "content": { route.content_type: { "schema": schema_ref(name) } } }),
+            );
+        }
+        operation.insert(
+            "responses".to_owned(),
+            Value::Object(Map::from_iter(route.statuses.iter().map(|status| {
+                let body = route
+                    .response
+                    .as_ref()
+                    .filter(|_| is_success(*status))
+                    .map(|(name, _)| json!({ "content": { route.content_type: { "schema": schema_ref(name) } } }));
+                (status.to_string(), body.unwrap_or_else(|| json!({ "description": "" })))
+            }))),
+        );
+
+        paths
+            .entry(openapi_path(route.path))
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .unwrap()
+            .insert(route.method.to_owned(), Value::Object(operation));
+    }
+
+    let openapi = json!({
+        "openapi": "3.0.3",
+        "info": { "title": "things", "version": "0.1.0" },
+        "paths": paths,
+        "components": { "schemas": schemas },
+    });
+
+    std::fs::write(
+        "../schemas/openapi.json",
+        serde_json::to_string_pretty(&openapi).unwrap(),
+    )
+    .unwrap();
+}
+
+/// Axum's `:name` path params become OpenAPI's `{name}` ones.
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => format!("{{{param}}}"),
+            None => segment.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_success(status: u16) -> bool {
+    (200..300).contains(&status)
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+/// Folds a [`RootSchema`]'s own schema plus any schemas it pulled into
+/// `definitions` (e.g. for nested types) into the shared `components/schemas`
+/// map, keyed by name. `schemars` addresses those nested schemas as
+/// `#/definitions/Name`; since they now live under `components/schemas`
+/// instead, every `$ref` is rewritten to match on the way in.
+fn register(schemas: &mut Map<String, Value>, name: &str, root: &RootSchema) {
+    for (name, schema) in &root.definitions {
+        schemas
+            .entry(name.clone())
+            .or_insert_with(|| rewrite_refs(serde_json::to_value(schema).unwrap()));
+    }
+    schemas
+        .entry(name.to_owned())
+        .or_insert_with(|| rewrite_refs(serde_json::to_value(&root.schema).unwrap()));
+}
+
+fn rewrite_refs(mut value: Value) -> Value {
+    match &mut value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                if let Some(name) = reference.strip_prefix("#/definitions/") {
+                    *reference = format!("#/components/schemas/{name}");
+                }
+            }
+            for nested in map.values_mut() {
+                *nested = rewrite_refs(std::mem::take(nested));
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                *item = rewrite_refs(std::mem::take(item));
+            }
+        }
+        _ => {}
+    }
+    value
 }
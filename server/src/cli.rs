@@ -0,0 +1,76 @@
+//! The `serve` binary's entrypoint, shared between the `server` and `api`
+//! crates so their two `main`s can't drift out of sync with each other.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use serde::Deserialize;
+
+use crate::registry::{self, ListStore};
+use crate::storage::Storage;
+
+#[derive(Parser)]
+pub struct Args {
+    /// Address to bind the HTTP server to. Overrides the config file.
+    #[arg(long)]
+    pub bind: Option<IpAddr>,
+
+    /// Port to bind the HTTP server to. Overrides the config file.
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// SQLite database file to persist lists to. Overrides the config file;
+    /// if neither is given, lists don't survive a restart.
+    #[arg(long)]
+    pub db: Option<PathBuf>,
+
+    /// TOML or JSON config file providing defaults and seed lists; format is
+    /// picked from the file extension, defaulting to TOML.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+}
+
+/// Startup settings plus named lists (title in `lists`, in order) the store
+/// is seeded with if they aren't already persisted.
+#[derive(Deserialize, Default)]
+struct Config {
+    bind: Option<IpAddr>,
+    port: Option<u16>,
+    db: Option<PathBuf>,
+    #[serde(default)]
+    lists: HashMap<String, Vec<String>>,
+}
+
+impl Config {
+    fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(serde_json::from_str(&contents)?),
+            _ => Ok(toml::from_str(&contents)?),
+        }
+    }
+}
+
+/// Resolves `args` against its optional config file and serves forever.
+/// Both `serve` binaries' `main` are just `tracing_subscriber::fmt::init()`
+/// followed by this call, so bind/port/db/config handling lives in one place.
+pub async fn run(args: Args) -> Result<()> {
+    let config = args.config.as_deref().map(Config::load).transpose()?.unwrap_or_default();
+
+    let bind = args.bind.or(config.bind).unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let port = args.port.or(config.port).unwrap_or(3000);
+
+    let storage = match args.db.or(config.db) {
+        Some(path) => Storage::open(path)?,
+        None => Storage::in_memory(),
+    };
+    let store = ListStore::open(storage)?;
+    store.seed(config.lists)?;
+
+    registry::serve(SocketAddr::from((bind, port)), store).await;
+
+    Ok(())
+}
@@ -1,11 +1,19 @@
-use schemars::JsonSchema;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, StringValidation},
+    JsonSchema,
+};
 
 use serde::{
     de::{self, Unexpected, Visitor},
     Deserialize, Serialize,
 };
 
-#[derive(Copy, Clone, PartialEq, Debug, JsonSchema)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Id {
     agent: u32,
     id: u32,
@@ -15,6 +23,105 @@ impl Id {
     pub fn new(agent: u32, id: u32) -> Self {
         Id { agent, id }
     }
+
+    pub fn agent(&self) -> u32 {
+        self.agent
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+/// Allocates unique, monotonically increasing [`Id`]s for a single agent.
+#[derive(Copy, Clone, Debug)]
+pub struct IdGenerator {
+    agent: u32,
+    next_id: u32,
+}
+
+impl IdGenerator {
+    /// Starts a fresh generator for a known agent, handing out `agent:0`, `agent:1`, ...
+    pub fn new(agent: u32) -> Self {
+        Self { agent, next_id: 0 }
+    }
+
+    /// Picks a random agent so that two independently started generators are
+    /// extremely unlikely to collide.
+    pub fn random() -> Self {
+        Self::new(rand::random())
+    }
+
+    /// Resumes a generator for `agent`, continuing after the highest id already seen
+    /// (e.g. when reloading a persisted `List`).
+    pub fn resume_from(agent: u32, highest_seen_id: u32) -> Self {
+        Self {
+            agent,
+            next_id: highest_seen_id + 1,
+        }
+    }
+
+    pub fn agent(&self) -> u32 {
+        self.agent
+    }
+
+    pub fn next(&mut self) -> Id {
+        let id = Id::new(self.agent, self.next_id);
+        self.next_id += 1;
+        id
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.agent, self.id)
+    }
+}
+
+impl FromStr for Id {
+    type Err = IdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let i = s.find(':').ok_or(IdParseError::MissingColon)?;
+        let agent = s[..i].parse().map_err(IdParseError::InvalidAgent)?;
+        let id = s[i + 1..].parse().map_err(IdParseError::InvalidId)?;
+        Ok(Id { agent, id })
+    }
+}
+
+impl TryFrom<&str> for Id {
+    type Error = IdParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum IdParseError {
+    #[error("missing ':' separator")]
+    MissingColon,
+    #[error("invalid agent: {0}")]
+    InvalidAgent(ParseIntError),
+    #[error("invalid id: {0}")]
+    InvalidId(ParseIntError),
+}
+
+impl JsonSchema for Id {
+    fn schema_name() -> String {
+        "Id".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        Schema::Object(SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^[0-9]+:[0-9]+$".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
 }
 
 struct IdVisitor;
@@ -24,8 +131,15 @@ impl Serialize for Id {
     where
         S: serde::Serializer,
     {
-        let id = format!("{}:{}", self.agent, self.id);
-        serializer.serialize_str(id.as_str())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            use serde::ser::SerializeTuple;
+            let mut tup = serializer.serialize_tuple(2)?;
+            tup.serialize_element(&self.agent)?;
+            tup.serialize_element(&self.id)?;
+            tup.end()
+        }
     }
 }
 
@@ -37,12 +151,21 @@ impl<'de> Visitor<'de> for IdVisitor {
     }
 
     fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
-        if let Some(i) = s.find(":") {
-            if let (Ok(agent), Ok(id)) = (s[..i].parse::<u32>(), s[i + 1..].parse::<u32>()) {
-                return Ok(Id { agent, id });
-            }
-        }
-        Err(de::Error::invalid_value(Unexpected::Str(s), &self))
+        s.parse()
+            .map_err(|_| de::Error::invalid_value(Unexpected::Str(s), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let agent = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let id = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(Id { agent, id })
     }
 }
 
@@ -51,7 +174,11 @@ impl<'de> Deserialize<'de> for Id {
     where
         D: serde::Deserializer<'de>,
     {
-        deserializer.deserialize_str(IdVisitor)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(IdVisitor)
+        } else {
+            deserializer.deserialize_tuple(2, IdVisitor)
+        }
     }
 }
 
@@ -59,12 +186,61 @@ impl<'de> Deserialize<'de> for Id {
 mod tests {
     use super::*;
 
-    use serde_test::{assert_tokens, Token};
+    use serde_test::{assert_tokens, Configure, Token};
 
     #[test]
     fn serde_id() {
         let id = Id { agent: 1, id: 2 };
 
-        assert_tokens(&id, &[Token::Str("1:2")]);
+        assert_tokens(&id.readable(), &[Token::Str("1:2")]);
+    }
+
+    #[test]
+    fn binary_encoding_is_a_compact_tuple() {
+        let id = Id { agent: 1, id: 2 };
+
+        assert_tokens(
+            &id.compact(),
+            &[
+                Token::Tuple { len: 2 },
+                Token::U32(1),
+                Token::U32(2),
+                Token::TupleEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn display_and_from_str_round_trip() {
+        let id = Id { agent: 1, id: 2 };
+
+        assert_eq!(id.to_string(), "1:2");
+        assert_eq!("1:2".parse::<Id>().unwrap(), id);
+        assert_eq!(Id::try_from("1:2").unwrap(), id);
+    }
+
+    #[test]
+    fn from_str_errors() {
+        assert!(matches!("12".parse::<Id>(), Err(IdParseError::MissingColon)));
+        assert!(matches!(
+            "a:2".parse::<Id>(),
+            Err(IdParseError::InvalidAgent(_))
+        ));
+        assert!(matches!("1:b".parse::<Id>(), Err(IdParseError::InvalidId(_))));
+    }
+
+    #[test]
+    fn id_generator_hands_out_increasing_ids() {
+        let mut generator = IdGenerator::new(1);
+
+        assert_eq!(generator.next(), Id { agent: 1, id: 0 });
+        assert_eq!(generator.next(), Id { agent: 1, id: 1 });
+    }
+
+    #[test]
+    fn id_generator_resumes_after_highest_seen_id() {
+        let mut generator = IdGenerator::resume_from(1, 4);
+
+        assert_eq!(generator.next(), Id { agent: 1, id: 5 });
     }
 }
@@ -1,5 +1,21 @@
+pub mod cli;
+pub mod id;
+pub mod lists;
+pub mod raw_value;
+pub mod registry;
+pub mod server;
+pub mod storage;
+pub mod store;
+
+pub use raw_value::RawValue;
+
+use std::fmt;
+
 use schemars::JsonSchema;
-use serde::Serialize;
+use serde::{
+    de::{self, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 
 #[derive(Serialize, JsonSchema)]
 pub struct List {
@@ -7,7 +23,104 @@ pub struct List {
     pub items: Vec<Item>,
 }
 
-#[derive(Serialize, JsonSchema)]
-pub struct Item {
-    pub value: String,
+/// A single entry in a [`List`]: either a successfully produced value, or an
+/// inline error recorded in place of it so a batch conversion can keep
+/// per-item failures without aborting the whole thing.
+///
+/// `Raw` holds any `value` that isn't a plain JSON string, passed through
+/// untouched. `Deserialize` is hand-written rather than `#[serde(untagged)]`
+/// because untagged dispatch buffers the input into a generic `Content` tree
+/// first, which defeats `RawValue`'s raw-capture protocol; deserializing the
+/// `value` field directly keeps the bytes intact.
+#[derive(Serialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Item {
+    Value { value: String },
+    Error { error: String },
+    Raw { value: RawValue },
+}
+
+impl<'de> Deserialize<'de> for Item {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ItemVisitor;
+
+        impl<'de> Visitor<'de> for ItemVisitor {
+            type Value = Item;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a list item value or an inline error")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Item, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let key: String = map
+                    .next_key()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                match key.as_str() {
+                    "value" => {
+                        let raw: RawValue = map.next_value()?;
+                        match serde_json::from_str::<String>(raw.get()) {
+                            Ok(value) => Ok(Item::Value { value }),
+                            Err(_) => Ok(Item::Raw { value: raw }),
+                        }
+                    }
+                    "error" => Ok(Item::Error {
+                        error: map.next_value()?,
+                    }),
+                    other => Err(de::Error::unknown_field(other, &["value", "error"])),
+                }
+            }
+        }
+
+        deserializer.deserialize_map(ItemVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_round_trips_as_a_mixed_array() {
+        let items = vec![
+            Item::Value {
+                value: "ok".to_owned(),
+            },
+            Item::Error {
+                error: "bad input".to_owned(),
+            },
+            Item::Value {
+                value: "ok".to_owned(),
+            },
+        ];
+
+        let json = serde_json::to_string(&items).unwrap();
+        assert_eq!(json, r#"[{"value":"ok"},{"error":"bad input"},{"value":"ok"}]"#);
+
+        let round_tripped: Vec<Item> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, items);
+    }
+
+    #[test]
+    fn plain_string_values_still_deserialize_as_value() {
+        let item: Item = serde_json::from_str(r#"{"value":"ok"}"#).unwrap();
+        assert_eq!(
+            item,
+            Item::Value {
+                value: "ok".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn raw_item_passes_its_payload_through_untouched() {
+        let item: Item = serde_json::from_str(r#"{"value":{"nested":[1,2,3]}}"#).unwrap();
+        let json = serde_json::to_string(&item).unwrap();
+        assert_eq!(json, r#"{"value":{"nested":[1,2,3]}}"#);
+    }
 }
@@ -1,5 +1,5 @@
 //! Wire protocol
-//! ```
+//! ```text
 //! {
 //!   "fork": u32,  // id of change in list
 //!   "changes": [
@@ -20,16 +20,39 @@
 //! }
 //! ```
 use std::collections::HashMap;
+use std::fmt;
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use im::Vector;
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
 
 use crate::id::Id;
+use crate::store::{ChangeStore, NullChangeStore};
 
-#[derive(Debug)]
 pub struct ServerList {
     list: List,
     max_agent_id: u32,
+    store: Box<dyn ChangeStore>,
+    operations: Vec<ServerOperation>,
+    undone: Vec<ServerOperation>,
+    next_operation_id: u32,
+    /// Changes held back by [`topo_order_by_parents`] because their parent
+    /// hasn't arrived yet; retried on every later `commit` alongside that
+    /// commit's own incoming changes.
+    buffered: Vec<Change>,
+}
+
+impl fmt::Debug for ServerList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ServerList")
+            .field("list", &self.list)
+            .field("max_agent_id", &self.max_agent_id)
+            .field("operations", &self.operations)
+            .finish()
+    }
 }
 
 impl ServerList {
@@ -37,46 +60,257 @@ impl ServerList {
         Self {
             max_agent_id: 0,
             list: List::new(0),
+            store: Box::new(NullChangeStore),
+            operations: vec![],
+            undone: vec![],
+            next_operation_id: 0,
+            buffered: vec![],
         }
     }
 
+    /// Rebuilds a `ServerList` by replaying `store`'s persisted history
+    /// through `apply_all`, then keeps `store` so future commits are
+    /// appended to it. `max_agent_id` is recovered from the highest agent id
+    /// seen in any replayed item, so freshly issued snapshots never reuse an
+    /// agent id that appears in the persisted history (an id handed out to a
+    /// snapshot that never committed anything is not recovered).
+    pub fn open(store: impl ChangeStore + 'static) -> Result<Self> {
+        let store: Box<dyn ChangeStore> = Box::new(store);
+        let changes = store.load()?;
+        let max_agent_id = changes
+            .iter()
+            .filter_map(|change| change.operation().item_id())
+            .map(|id| id.agent())
+            .max()
+            .unwrap_or(0);
+
+        let mut list = List::new(0);
+        list.apply_all(&changes)?;
+
+        Ok(Self {
+            max_agent_id,
+            list,
+            store,
+            operations: vec![],
+            undone: vec![],
+            next_operation_id: 0,
+            buffered: vec![],
+        })
+    }
+
     pub fn snapshot(&mut self) -> List {
         self.max_agent_id += 1;
         self.list.snapshot(self.max_agent_id)
     }
 
+    /// Configures how [`List::apply`] settles a genuinely-conflicting field
+    /// edit for this server (and every snapshot handed out from here on);
+    /// defaults to [`MergeStrategy::Manual`].
+    pub fn with_merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.list.merge_strategy = strategy;
+        self
+    }
+
+    /// The hashes of changes that currently have no known children: the sync
+    /// position a client should send back on its next commit.
+    pub fn heads(&self) -> Vec<ChangeHash> {
+        self.list.changes.heads()
+    }
+
+    /// The changes a client holding `their_heads` is missing, so it can pull
+    /// exactly what it lacks instead of the whole history.
+    pub fn changes_missing(&self, their_heads: &[ChangeHash]) -> Vec<Change> {
+        self.list.changes.missing_since(their_heads)
+    }
+
+    /// Decodes `message`, commits its changes, and returns the confirmed
+    /// changes in the same kind of envelope, echoing `message.fork` back.
+    pub fn commit_message(&mut self, message: &SyncMessage) -> Result<SyncMessage> {
+        let changes = self.commit(&message.changes)?;
+        Ok(SyncMessage {
+            fork: message.fork,
+            changes,
+        })
+    }
+
     pub fn commit(&mut self, changes: &[Change]) -> Result<Vec<Change>> {
         if !self.list.changes.is_at_head() {
             unreachable!("root list must always be at head");
         }
-        // TODO: handle the same set of changes being committed again
-        let changes = squash_changes(changes);
+        // A well-behaved client always sends its fork point as `changes[0]`,
+        // but `changes` arrives straight off the wire (see
+        // `SyncMessage::from_json` in `crate::server`), so an empty array is
+        // just malformed input, not a bug to `unreachable!` on.
+        if changes.is_empty() {
+            return Err(ListError::EmptyCommit.into());
+        }
+        // `changes[0]` is the fork point the client built on, always already
+        // known to us; drop any *other* change whose hash we already have so
+        // resending the same batch (e.g. after a dropped ack) is a no-op.
+        let anchor = changes[0].clone();
+        let new_incoming: Vec<Change> = changes[1..]
+            .iter()
+            .filter(|change| !self.list.changes.contains_hash(&change.hash()))
+            .cloned()
+            .collect();
+        if new_incoming.is_empty() && self.buffered.is_empty() {
+            return Ok(vec![]);
+        }
+        // Retry anything buffered from an earlier commit (its parent may have
+        // landed since) alongside this commit's own incoming changes.
+        let mut candidates = std::mem::take(&mut self.buffered);
+        candidates.extend(new_incoming);
+        let TopoOrder { resolved, unresolved } = topo_order_by_parents(&self.list.changes.by_hash, candidates);
+        self.buffered = unresolved;
+        if resolved.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut changes = vec![anchor];
+        changes.extend(resolved);
+        let changes = squash_changes(&changes);
         if changes[0] == *self.list.changes.changes.last().unwrap() {
             // TODO: double check if this branch is required. I think `transform` will just do the
             // right thing
-            return Ok(self
-                .list
-                .apply_all(&changes[1..])
-                .map(|_| changes[1..].to_vec())?);
+            let confirmed = changes[1..].to_vec();
+            self.list.apply_all(&confirmed)?;
+            self.store.append(&confirmed)?;
+            self.record_operation(confirmed.clone());
+            return Ok(confirmed);
         } else {
-            let mut confirmed_changes = self.list.changes.changes_since(&changes[0]).to_vec();
+            let mut confirmed_changes = self.list.changes.changes_since(&changes[0]);
             let incoming_changes = &changes[1..];
             let new_changes = transform(&confirmed_changes, incoming_changes);
 
-            return Ok(self.list.apply_all(&new_changes).map(|_| {
-                confirmed_changes.extend_from_slice(&new_changes);
-                confirmed_changes
-            })?);
+            self.list.apply_all(&new_changes)?;
+            self.store.append(&new_changes)?;
+            self.record_operation(new_changes.clone());
+            confirmed_changes.extend_from_slice(&new_changes);
+            return Ok(confirmed_changes);
+        }
+    }
+
+    /// Appends a new entry to the operation log for a commit's newly-applied
+    /// changes, and drops the redo stack: like most editors, a fresh commit
+    /// invalidates whatever was previously undone.
+    fn record_operation(&mut self, changes: Vec<Change>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.next_operation_id += 1;
+        let parent = self.operations.last().map(|op| op.id);
+        self.operations.push(ServerOperation {
+            id: self.next_operation_id,
+            parent,
+            changes,
+        });
+        self.undone.clear();
+    }
+
+    /// The operation log in commit order; only covers commits made since the
+    /// current process started (see [`ServerOperation`]).
+    pub fn operations(&self) -> &[ServerOperation] {
+        &self.operations
+    }
+
+    /// Reverts exactly the changes `operation_id` applied, replaying every
+    /// later operation's changes back on top so only that one operation's
+    /// effect disappears rather than resetting the whole list to an old
+    /// snapshot. Moves the operation onto the redo stack.
+    ///
+    /// Only undoes the in-memory [`ServerOperation`] log — see its doc
+    /// comment for why that makes this unsafe to rely on across a restart or
+    /// with more than one syncing client.
+    pub fn undo(&mut self, operation_id: u32) -> Result<()> {
+        let index = self
+            .operations
+            .iter()
+            .position(|op| op.id == operation_id)
+            .ok_or(ListError::NotFound)?;
+
+        for op in self.operations[index..].iter().rev() {
+            for change in op.changes.iter().rev() {
+                self.list.revert(change)?;
+            }
+        }
+        for op in &self.operations[index + 1..] {
+            for change in &op.changes {
+                self.list.apply(change)?;
+            }
+        }
+
+        let operation = self.operations.remove(index);
+        self.undone.push(operation);
+        Ok(())
+    }
+
+    /// Re-applies the most recently undone operation, restoring it to its
+    /// original position in the log. Errors if nothing has been undone, or if
+    /// a commit landed since — the same way most editors drop the redo stack
+    /// the moment a fresh edit happens.
+    pub fn redo(&mut self) -> Result<()> {
+        let operation = self.undone.pop().ok_or(ListError::NotFound)?;
+
+        let index = self
+            .operations
+            .iter()
+            .position(|op| op.id > operation.id)
+            .unwrap_or(self.operations.len());
+
+        for op in self.operations[index..].iter().rev() {
+            for change in op.changes.iter().rev() {
+                self.list.revert(change)?;
+            }
+        }
+        for change in &operation.changes {
+            self.list.apply(change)?;
+        }
+        for op in &self.operations[index..] {
+            for change in &op.changes {
+                self.list.apply(change)?;
+            }
         }
+
+        self.operations.insert(index, operation);
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+/// One successful [`ServerList::commit`], recorded in an in-memory,
+/// append-only log: `parent` links back to the operation committed just
+/// before it, and `changes` are exactly the new [`Change`]s that commit
+/// applied — enough for [`ServerList::undo`]/[`ServerList::redo`] to invert
+/// an operation in place, touching only the materialized items and
+/// conflicts, without rewriting the change history itself.
+///
+/// [`ServerList::undo`] removes the targeted entry outright rather than
+/// recording the undo as a new operation, so this log (and the undo/redo
+/// stack built on it) is strictly in-process, single-writer state: it isn't
+/// reconstructed by [`ServerList::open`], so only operations committed since
+/// the current process started can be undone — and undo/redo never touch
+/// [`List::changes`] or the [`crate::store::ChangeStore`] a commit writes
+/// through to, so an undo is invisible to both. Concretely: the undone
+/// [`Change`]s are still sitting in the durable store, so a restart replays
+/// and resurrects them; and `heads()`/`changes_missing()` still report the
+/// undone change as confirmed history, so a client forked from before the
+/// undo can have an edit matched against a change that, materially, no
+/// longer applies. Don't wire undo/redo up to a multi-client or
+/// restart-surviving deployment without addressing both.
+#[derive(Clone, Debug)]
+pub struct ServerOperation {
+    pub id: u32,
+    pub parent: Option<u32>,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone)]
 pub struct List {
     agent_id: u32,
     max_item_id: u32,
+    text_seq: u32,
     items: Vec<ListItem>,
     changes: ChangeLog,
+    conflicts: Vec<Conflict>,
+    merge_strategy: MergeStrategy,
 }
 
 impl List {
@@ -84,8 +318,11 @@ impl List {
         Self {
             agent_id,
             max_item_id: 0,
+            text_seq: 0,
             items: vec![],
             changes: ChangeLog::new(),
+            conflicts: vec![],
+            merge_strategy: MergeStrategy::Manual,
         }
     }
 
@@ -93,13 +330,22 @@ impl List {
         Self {
             agent_id,
             max_item_id: 0,
+            text_seq: 0,
             items: self.items.clone(),
-            changes: self.changes.clone(), // TODO: do we need the whole change log?
+            changes: self.changes.clone(),
+            conflicts: self.conflicts.clone(),
+            merge_strategy: self.merge_strategy,
         }
     }
 
     fn iter(&self) -> impl Iterator<Item = &ListItem> {
-        self.items.iter()
+        self.items.iter().filter(|item| !item.deleted)
+    }
+
+    /// Live (non-deleted) items in display order, for callers outside this
+    /// module that just need to read the list (e.g. [`crate::registry`]).
+    pub fn items(&self) -> impl Iterator<Item = &ListItem> {
+        self.iter()
     }
 
     fn next_id(&mut self) -> Id {
@@ -157,20 +403,128 @@ impl List {
                 self.items.push(item.clone());
             }
             Remove(item) => {
-                self.items.remove(
-                    self.items
-                        .iter()
-                        .position(|itm| itm.id == item.id)
-                        .ok_or(ListError::NotFound)?,
-                );
+                let idx = self
+                    .items
+                    .iter()
+                    .position(|itm| itm.id == item.id)
+                    .ok_or(ListError::NotFound)?;
+
+                self.conflicts.retain(|conflict| {
+                    !(conflict.id == item.id && matches!(&conflict.values, ConflictValues::Deleted { .. }))
+                });
+
+                let current = &self.items[idx];
+                if !current.deleted
+                    && (current.title != item.title || current.done != item.done || current.order != item.order)
+                {
+                    // The item was edited since this delete's snapshot was
+                    // taken: keep the edited item live for now and surface
+                    // the clash rather than silently discarding the edit.
+                    self.conflicts.push(Conflict {
+                        id: item.id,
+                        values: ConflictValues::Deleted { edit: None },
+                    });
+                } else {
+                    self.items[idx].deleted = true;
+                }
             }
-            Edit(old_item, new_item) => {
-                let item = self
+            Edit(edit) => {
+                let idx = self
                     .items
-                    .iter_mut()
-                    .find(|itm| itm.id == old_item.id)
+                    .iter()
+                    .position(|itm| itm.id == edit.id)
                     .ok_or(ListError::NotFound)?;
-                *item = new_item.clone();
+
+                self.conflicts.retain(|conflict| {
+                    !(conflict.id == edit.id && matches!(&conflict.values, ConflictValues::Deleted { .. }))
+                });
+
+                if self.items[idx].deleted && !edit.undelete {
+                    // A confirmed delete landed before this edit's branch knew
+                    // about it: keep the item deleted for now and surface the
+                    // clash rather than silently discarding the edit or
+                    // reviving the item unasked.
+                    self.conflicts.push(Conflict {
+                        id: edit.id,
+                        values: ConflictValues::Deleted { edit: Some(edit.clone()) },
+                    });
+                    return Ok(());
+                }
+                self.items[idx].deleted = false;
+
+                apply_text_ops(&mut self.items[idx].title_cells, &edit.title_ops);
+                self.items[idx].title = render_title(&self.items[idx].title_cells);
+
+                if let Some((base, new)) = edit.done {
+                    self.conflicts.retain(|conflict| {
+                        !(conflict.id == edit.id && matches!(&conflict.values, ConflictValues::Done { .. }))
+                    });
+                    let resolved = match merge_field(base, new, self.items[idx].done) {
+                        FieldMerge::Value(value) => Some(value),
+                        FieldMerge::Conflict { base, server, incoming } => match self.merge_strategy {
+                            MergeStrategy::PreferServer => Some(server),
+                            MergeStrategy::PreferIncoming => Some(incoming),
+                            MergeStrategy::PreferLatest => {
+                                Some(if change.timestamp >= self.items[idx].done_at {
+                                    incoming
+                                } else {
+                                    server
+                                })
+                            }
+                            MergeStrategy::Manual => {
+                                self.conflicts.push(Conflict {
+                                    id: edit.id,
+                                    values: ConflictValues::Done {
+                                        base,
+                                        values: (server, incoming),
+                                    },
+                                });
+                                None
+                            }
+                        },
+                    };
+                    if let Some(done) = resolved {
+                        if done != self.items[idx].done {
+                            self.items[idx].done_at = change.timestamp;
+                        }
+                        self.items[idx].done = done;
+                    }
+                }
+                if let Some((base, new)) = edit.order {
+                    self.conflicts.retain(|conflict| {
+                        !(conflict.id == edit.id && matches!(&conflict.values, ConflictValues::Order { .. }))
+                    });
+                    let resolved = match merge_field(base, new, self.items[idx].order) {
+                        FieldMerge::Value(value) => Some(value),
+                        FieldMerge::Conflict { base, server, incoming } => match self.merge_strategy {
+                            MergeStrategy::PreferServer => Some(server),
+                            MergeStrategy::PreferIncoming => Some(incoming),
+                            MergeStrategy::PreferLatest => {
+                                Some(if change.timestamp >= self.items[idx].order_at {
+                                    incoming
+                                } else {
+                                    server
+                                })
+                            }
+                            MergeStrategy::Manual => {
+                                self.conflicts.push(Conflict {
+                                    id: edit.id,
+                                    values: ConflictValues::Order {
+                                        base,
+                                        values: (server, incoming),
+                                    },
+                                });
+                                None
+                            }
+                        },
+                    };
+                    if let Some(order) = resolved {
+                        if order != self.items[idx].order {
+                            self.items[idx].order_at = change.timestamp;
+                        }
+                        self.items[idx].order = order;
+                    }
+                }
             }
             Root => unreachable!("cannot apply the root operation"),
         }
@@ -189,16 +543,30 @@ impl List {
                 );
             }
             Remove(item) => {
-                self.items.push(item.clone());
-                // TODO: sort?
+                let idx = self
+                    .items
+                    .iter()
+                    .position(|itm| itm.id == item.id)
+                    .ok_or(ListError::NotFound)?;
+                self.items[idx].deleted = false;
             }
-            Edit(old_item, new_item) => {
+            Edit(edit) => {
                 let item = self
                     .items
                     .iter_mut()
-                    .find(|itm| itm.id == new_item.id)
+                    .find(|itm| itm.id == edit.id)
                     .ok_or(ListError::NotFound)?;
-                *item = old_item.clone();
+                revert_text_ops(&mut item.title_cells, &edit.title_ops);
+                item.title = render_title(&item.title_cells);
+                if let Some((old, _)) = edit.done {
+                    item.done = old;
+                }
+                if let Some((old, _)) = edit.order {
+                    item.order = old;
+                }
+                if edit.undelete {
+                    item.deleted = true;
+                }
             }
             Root => unreachable!("cannot revert the root operation"),
         }
@@ -217,12 +585,14 @@ impl List {
 
     pub fn add(&mut self, title: impl Into<String>) -> ListItem {
         let title = title.into();
-        if let Some(item) = self.items.iter().find(|item| title == item.title) {
+        if let Some(item) = self.items.iter().find(|item| !item.deleted && title == item.title) {
             item.clone()
         } else {
+            let title_cells = new_title_cells(self.agent_id, &mut self.text_seq, &title);
             let item = ListItem {
                 id: self.next_id(),
                 title,
+                title_cells,
                 done: false,
                 order: self
                     .items
@@ -230,6 +600,9 @@ impl List {
                     .map(|item| item.order)
                     .fold(0f32, f32::max)
                     + 1f32,
+                done_at: now_millis(),
+                order_at: now_millis(),
+                deleted: false,
             };
             self.push(Operation::Add(item.clone()))
                 .expect("add cannot fail");
@@ -242,7 +615,7 @@ impl List {
         let item = self
             .items
             .iter()
-            .find(|itm| itm.id == id)
+            .find(|itm| !itm.deleted && itm.id == id)
             .ok_or(ListError::NotFound)?
             .clone();
         self.push(Operation::Remove(item.clone()))?;
@@ -254,11 +627,47 @@ impl List {
         let old_item = self
             .items
             .iter()
-            .find(|item| item.id == id)
+            .find(|item| !item.deleted && item.id == id)
             .ok_or(ListError::NotFound)?
             .clone();
-        let new_item = update.update(&old_item);
-        self.push(Operation::Edit(old_item, new_item.clone()))?;
+
+        let title_ops = match &update.title {
+            Some(new_title) if *new_title != old_item.title => diff_title_ops(
+                &old_item.title_cells,
+                new_title,
+                self.agent_id,
+                &mut self.text_seq,
+            ),
+            _ => vec![],
+        };
+        let done = update
+            .done
+            .filter(|&done| done != old_item.done)
+            .map(|done| (old_item.done, done));
+        let order = update
+            .order
+            .filter(|&order| order != old_item.order)
+            .map(|order| (old_item.order, order));
+
+        let mut new_item = old_item.clone();
+        apply_text_ops(&mut new_item.title_cells, &title_ops);
+        new_item.title = render_title(&new_item.title_cells);
+        if let Some((_, done)) = done {
+            new_item.done = done;
+        }
+        if let Some((_, order)) = order {
+            new_item.order = order;
+        }
+
+        let edit = ItemEdit {
+            id,
+            title_ops,
+            resulting_title: new_item.title.clone(),
+            done,
+            order,
+            undelete: false,
+        };
+        self.push(Operation::Edit(edit))?;
         Ok(new_item)
     }
 
@@ -268,7 +677,7 @@ impl List {
         let move_item_position = self
             .items
             .iter()
-            .position(|item| item.id == move_id)
+            .position(|item| !item.deleted && item.id == move_id)
             .ok_or(ListError::NotFound)?;
 
         let (low, high) = if let Some(after_id) = after_id {
@@ -276,7 +685,7 @@ impl List {
             let position = self
                 .items
                 .iter()
-                .position(|itm| itm.id == after_id)
+                .position(|itm| !itm.deleted && itm.id == after_id)
                 .ok_or(ListError::NotFound)?;
             if position == self.items.len() {
                 (
@@ -301,17 +710,151 @@ impl List {
         Ok(())
     }
 
-    pub fn changes_to_commit(&self) -> &[Change] {
+    /// The agent id this snapshot was issued under; doubles as a connection
+    /// identity for transports (e.g. [`crate::server`]) that need to tell a
+    /// client's own commits apart from changes broadcast back to it.
+    pub fn agent_id(&self) -> u32 {
+        self.agent_id
+    }
+
+    /// Outstanding conflicts across every item, populated when a three-way
+    /// merge in [`apply`](Self::apply) finds the base, server, and incoming
+    /// values all distinct under [`MergeStrategy::Manual`], or when a delete
+    /// and an edit land on the same item. A UI can offer "pick one" for each and
+    /// call [`resolve`](Self::resolve) with the answer.
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// Commits `resolution` as the final value for a conflicted field,
+    /// clearing the conflict. Errors with [`ListError::NotFound`] if `id` has
+    /// no outstanding conflict for that field — use [`update`](Self::update)
+    /// for an ordinary edit.
+    pub fn resolve(&mut self, id: impl Into<Id>, resolution: Resolution) -> Result<ListItem> {
+        let id = id.into();
+        let conflict = self
+            .conflicts
+            .iter()
+            .find(|conflict| conflict.id == id && conflict.values.matches(&resolution))
+            .ok_or(ListError::NotFound)?
+            .clone();
+
+        let old_item = self
+            .items
+            .iter()
+            .find(|item| item.id == id)
+            .ok_or(ListError::NotFound)?
+            .clone();
+
+        match resolution {
+            Resolution::Done(done) => {
+                self.push(Operation::Edit(ItemEdit {
+                    id,
+                    title_ops: vec![],
+                    resulting_title: old_item.title,
+                    done: Some((old_item.done, done)),
+                    order: None,
+                    undelete: false,
+                }))?;
+            }
+            Resolution::Order(order) => {
+                self.push(Operation::Edit(ItemEdit {
+                    id,
+                    title_ops: vec![],
+                    resulting_title: old_item.title,
+                    done: None,
+                    order: Some((old_item.order, order)),
+                    undelete: false,
+                }))?;
+            }
+            Resolution::ConfirmDelete => {
+                // Whichever branch's view is live, confirming the delete
+                // means ending up deleted; `apply`'s `Remove` arm is already
+                // idempotent against an item that's deleted or unchanged.
+                self.push(Operation::Remove(old_item))?;
+            }
+            Resolution::KeepEdited => {
+                // Replay the edit that lost to the delete (if any) with
+                // `undelete` set, so `apply`'s `Edit` arm revives the item
+                // instead of re-raising the conflict.
+                let stored_edit = match conflict.values {
+                    ConflictValues::Deleted { edit } => edit,
+                    _ => None,
+                };
+                let edit = stored_edit.unwrap_or(ItemEdit {
+                    id,
+                    title_ops: vec![],
+                    resulting_title: old_item.title,
+                    done: None,
+                    order: None,
+                    undelete: false,
+                });
+                self.push(Operation::Edit(ItemEdit { undelete: true, ..edit }))?;
+            }
+        }
+
+        Ok(self.items.iter().find(|item| item.id == id).unwrap().clone())
+    }
+
+    pub fn changes_to_commit(&self) -> Vec<Change> {
         self.changes.to_commit()
     }
+
+    /// The pending changes since the fork point, wrapped in the envelope a
+    /// server's `ServerList::commit_message` expects.
+    pub fn to_sync_message(&self) -> SyncMessage {
+        SyncMessage {
+            fork: self.changes.fork as u32,
+            changes: self.changes_to_commit(),
+        }
+    }
+}
+
+/// The wire envelope described at the top of this module: the changes a
+/// client wants to commit (or a server hands back as confirmed), alongside
+/// the fork point they were built on.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SyncMessage {
+    pub fork: u32,
+    pub changes: Vec<Change>,
+}
+
+impl SyncMessage {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct ListItem {
     pub id: Id,
     pub title: String,
     pub done: bool,
     pub order: f32,
+    /// The title's underlying RGA cells, carried alongside the rendered
+    /// `title` so concurrent renames can merge character-by-character
+    /// instead of one whole string clobbering the other.
+    title_cells: Vec<Cell>,
+    /// When `done`/`order` last changed value (by a clean edit, or by a
+    /// conflict a [`MergeStrategy`] settled in the incoming edit's favour),
+    /// so [`MergeStrategy::PreferLatest`] has something to compare a
+    /// conflicting incoming edit's own timestamp against.
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    #[schemars(with = "i64")]
+    done_at: DateTime<Utc>,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    #[schemars(with = "i64")]
+    order_at: DateTime<Utc>,
+    /// Tombstone for a confirmed removal. Deleted items stay in `items`
+    /// (rather than being spliced out) so a concurrent edit still has
+    /// something to land on and conflict against; [`List::iter`] and the
+    /// other lookups hide them as if they were gone.
+    #[serde(default)]
+    deleted: bool,
 }
 
 impl Into<Id> for ListItem {
@@ -353,35 +896,119 @@ impl UpdateListItem {
         self.order = Some(order);
         self
     }
-
-    fn update(self, old_item: &ListItem) -> ListItem {
-        let mut new_item = old_item.clone();
-        if let Some(title) = self.title {
-            new_item.title = title;
-        }
-        if let Some(done) = self.done {
-            new_item.done = done;
-        }
-        if let Some(order) = self.order {
-            new_item.order = order;
-        }
-
-        new_item
-    }
 }
 
 #[derive(thiserror::Error, Debug)]
 enum ListError {
     #[error("Item not found")]
     NotFound,
+    #[error("commit requires at least a fork anchor, got an empty changes list")]
+    EmptyCommit,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "operation", content = "item", rename_all = "lowercase")]
 pub enum Operation {
     Root,
     Add(ListItem),
     Remove(ListItem),
-    Edit(ListItem, ListItem),
+    Edit(ItemEdit),
+}
+
+/// A character-level merge of a title rename plus a plain field diff for
+/// `done`/`order`. Concurrent `title_ops` from two branches union cleanly
+/// (insert/delete are keyed by cell id), so renaming on one client and
+/// ticking `done` on another never clobbers either edit.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ItemEdit {
+    pub id: Id,
+    title_ops: Vec<TextOp>,
+    /// The title the editing client ended up with, kept alongside the ops
+    /// purely so conflict-detection heuristics (duplicate-title dedup) don't
+    /// need to replay the ops to compare against it.
+    resulting_title: String,
+    done: Option<(bool, bool)>,
+    order: Option<(f32, f32)>,
+    /// Set by [`List::resolve`]'s [`Resolution::KeepEdited`] to revive an
+    /// item a concurrent branch had already deleted, so this edit can land
+    /// instead of being turned back into a [`ConflictValues::Deleted`].
+    #[serde(default)]
+    undelete: bool,
+}
+
+/// A clash [`List::resolve`] has to settle: either a same-field edit that
+/// couldn't be reconciled automatically (`base`, the server's value, and the
+/// incoming value were all distinct), in which case the item keeps showing
+/// the server's pre-conflict value through [`List::iter`]; or a delete and an
+/// edit on the same item ([`ConflictValues::Deleted`]), in which case the
+/// item stays hidden if the delete landed first and stays live if the edit
+/// did.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    pub id: Id,
+    pub values: ConflictValues,
+}
+
+/// What a [`Conflict`] is torn between: for a scalar field, the base value
+/// and the two candidates (server, incoming); for a delete-vs-edit clash,
+/// the edit that may need replaying.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConflictValues {
+    Done { base: bool, values: (bool, bool) },
+    Order { base: f32, values: (f32, f32) },
+    /// A delete and an edit landed on the same item from branches that each
+    /// didn't know about the other's change. `edit` is the edit to replay if
+    /// [`Resolution::KeepEdited`] wins; `None` when the delete's own branch
+    /// raised the conflict (nothing to replay, just undelete and keep what's
+    /// already there).
+    Deleted { edit: Option<ItemEdit> },
+}
+
+impl ConflictValues {
+    fn matches(&self, resolution: &Resolution) -> bool {
+        matches!(
+            (self, resolution),
+            (ConflictValues::Done { .. }, Resolution::Done(_))
+                | (ConflictValues::Order { .. }, Resolution::Order(_))
+                | (
+                    ConflictValues::Deleted { .. },
+                    Resolution::KeepEdited | Resolution::ConfirmDelete
+                )
+        )
+    }
+}
+
+/// The payload for [`List::resolve`]: which field to settle, and the value
+/// to settle it on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolution {
+    Done(bool),
+    Order(f32),
+    /// Revive a [`ConflictValues::Deleted`] item, replaying its stored edit
+    /// if there was one.
+    KeepEdited,
+    /// Settle a [`ConflictValues::Deleted`] conflict by confirming the
+    /// delete.
+    ConfirmDelete,
+}
+
+/// How [`List::apply`] settles a field where the edit's base, the server's
+/// current value, and the incoming value are all distinct (see
+/// [`merge_field`]). Configured once via [`ServerList::with_merge_strategy`]
+/// and inherited by every snapshot handed out afterward, so every replica
+/// resolves the same edit the same way. Clean three-way merges (any two of
+/// the three agreeing) go through untouched regardless of the strategy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeStrategy {
+    /// Keep whatever the server already has.
+    PreferServer,
+    /// Take the incoming edit's value.
+    PreferIncoming,
+    /// Take whichever edit — the incoming one, or whatever last set the
+    /// server's value — has the later timestamp.
+    PreferLatest,
+    /// Don't auto-resolve: materialize a [`Conflict`] for [`List::resolve`].
+    Manual,
 }
 
 enum TransformResult {
@@ -389,133 +1016,638 @@ enum TransformResult {
     Skip(Change),
 }
 
-#[derive(Clone, PartialEq, Debug)]
-pub struct Change {
-    timestamp: DateTime<Utc>,
-    operation: Operation,
+/// An identifier for a single character cell, unique across all agents.
+#[derive(Serialize, Deserialize, JsonSchema, Copy, Clone, PartialEq, Eq, Debug)]
+struct CellId {
+    agent: u32,
+    seq: u32,
 }
 
-impl Change {
-    fn root() -> Self {
-        Self::new(Operation::Root)
+/// One character of a title, addressed by [`CellId`] so renames can be
+/// merged rather than overwritten. Deleted cells are kept as tombstones so
+/// later inserts can still anchor `after` a cell that no longer shows.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+struct Cell {
+    id: CellId,
+    after: Option<CellId>,
+    ch: char,
+    deleted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum TextOp {
+    Insert {
+        id: CellId,
+        after: Option<CellId>,
+        ch: char,
+    },
+    Delete {
+        id: CellId,
+    },
+}
+
+fn render_title(cells: &[Cell]) -> String {
+    cells.iter().filter(|cell| !cell.deleted).map(|cell| cell.ch).collect()
+}
+
+fn new_title_cells(agent_id: u32, text_seq: &mut u32, title: &str) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut after = None;
+    for ch in title.chars() {
+        *text_seq += 1;
+        let id = CellId {
+            agent: agent_id,
+            seq: *text_seq,
+        };
+        cells.push(Cell {
+            id,
+            after,
+            ch,
+            deleted: false,
+        });
+        after = Some(id);
     }
+    cells
+}
 
-    fn new(operation: Operation) -> Self {
-        Self {
-            timestamp: Utc::now(),
-            operation,
-        }
+/// Diffs `new_title` against the visible string of `cells` by common
+/// prefix/suffix, producing inserts/deletes for just the changed middle.
+fn diff_title_ops(cells: &[Cell], new_title: &str, agent_id: u32, text_seq: &mut u32) -> Vec<TextOp> {
+    let live: Vec<&Cell> = cells.iter().filter(|cell| !cell.deleted).collect();
+    let old_chars: Vec<char> = live.iter().map(|cell| cell.ch).collect();
+    let new_chars: Vec<char> = new_title.chars().collect();
+
+    let mut prefix = 0;
+    while prefix < old_chars.len() && prefix < new_chars.len() && old_chars[prefix] == new_chars[prefix] {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < old_chars.len() - prefix
+        && suffix < new_chars.len() - prefix
+        && old_chars[old_chars.len() - 1 - suffix] == new_chars[new_chars.len() - 1 - suffix]
+    {
+        suffix += 1;
     }
 
-    fn update_item_id(&mut self, id_map: &HashMap<Id, Id>) {
-        match &mut self.operation {
-            Operation::Remove(item) if id_map.contains_key(&item.id) => item.id = id_map[&item.id],
-            Operation::Edit(from_item, to_item) if id_map.contains_key(&from_item.id) => {
-                let new_id = id_map[&from_item.id];
-                from_item.id = new_id;
-                to_item.id = new_id;
-            }
-            _ => {}
-        }
+    let mut ops = Vec::new();
+    for cell in &live[prefix..old_chars.len() - suffix] {
+        ops.push(TextOp::Delete { id: cell.id });
     }
+
+    let mut after = if prefix == 0 { None } else { Some(live[prefix - 1].id) };
+    for &ch in &new_chars[prefix..new_chars.len() - suffix] {
+        *text_seq += 1;
+        let id = CellId {
+            agent: agent_id,
+            seq: *text_seq,
+        };
+        ops.push(TextOp::Insert { id, after, ch });
+        after = Some(id);
+    }
+
+    ops
 }
 
-#[derive(Clone, Debug)]
-pub struct ChangeLog {
-    head: usize,
-    fork: usize,
-    changes: Vec<Change>,
+/// The outcome of a [`merge_field`] three-way merge.
+enum FieldMerge<T> {
+    Value(T),
+    Conflict { base: T, server: T, incoming: T },
 }
 
-impl ChangeLog {
-    fn new() -> Self {
-        Self {
-            head: 0,
-            fork: 0,
-            changes: vec![Change::root()],
+/// Three-way merge for a scalar field: `base` is the field's value when the
+/// edit's snapshot branched, `incoming`
+/// is the value the committing edit wants, and `current` is whatever the
+/// server already holds (which may have moved on via other confirmed
+/// changes). If the edit didn't really change anything (`incoming == base`)
+/// the server's value wins untouched; if the server hasn't moved either
+/// (`current == base`) the incoming edit applies cleanly; if both sides
+/// agree on the same value there's nothing to pick between. Only when all
+/// three differ is this a genuine conflict, materialized rather than
+/// silently resolved so a UI can offer "pick one".
+fn merge_field<T: PartialEq>(base: T, incoming: T, current: T) -> FieldMerge<T> {
+    if incoming == base {
+        FieldMerge::Value(current)
+    } else if current == base || incoming == current {
+        FieldMerge::Value(incoming)
+    } else {
+        FieldMerge::Conflict {
+            base,
+            server: current,
+            incoming,
         }
     }
+}
 
-    fn push(&mut self, op: Operation) -> &Change {
-        if !self.is_at_head() {
-            panic!("cannot push when not at head")
+/// Applies ops in order; inserting a cell that's already present (e.g. the
+/// same change replayed) is a no-op, which is what makes concurrent
+/// insert/delete sets safe to union.
+fn apply_text_ops(cells: &mut Vec<Cell>, ops: &[TextOp]) {
+    for op in ops {
+        match op {
+            TextOp::Insert { id, after, ch } => insert_cell(cells, *id, *after, *ch),
+            TextOp::Delete { id } => {
+                if let Some(cell) = cells.iter_mut().find(|cell| cell.id == *id) {
+                    cell.deleted = true;
+                }
+            }
         }
-        let change = Change::new(op);
-        self.changes.push(change);
-        self.next().expect("just pushed one")
     }
+}
 
-    fn push_change(&mut self, change: &Change) -> &Change {
-        self.changes.push(change.clone());
-        self.next().expect("just pushed one")
+fn revert_text_ops(cells: &mut Vec<Cell>, ops: &[TextOp]) {
+    for op in ops.iter().rev() {
+        match op {
+            TextOp::Insert { id, .. } => cells.retain(|cell| cell.id != *id),
+            TextOp::Delete { id } => {
+                if let Some(cell) = cells.iter_mut().find(|cell| cell.id == *id) {
+                    cell.deleted = false;
+                }
+            }
+        }
     }
+}
 
-    fn changes_since(&self, change: &Change) -> &[Change] {
-        if let Some(i) = self.changes.iter().position(|c| c == change) {
-            &self.changes[i + 1..]
-        } else {
-            unreachable!("the change must exist!")
-        }
+fn insert_cell(cells: &mut Vec<Cell>, id: CellId, after: Option<CellId>, ch: char) {
+    if cells.iter().any(|cell| cell.id == id) {
+        return;
     }
 
-    fn is_at_head(&self) -> bool {
-        // TODO: this means the fork change is included in changes_to_commit
-        self.head == self.changes.len() - 1
+    let anchor = match after {
+        None => 0,
+        Some(after_id) => match cells.iter().position(|cell| cell.id == after_id) {
+            Some(pos) => pos + 1,
+            None => cells.len(),
+        },
+    };
+
+    // Concurrent inserts anchored at the same cell are ordered by descending
+    // agent id, so a later edit's characters land right after the shared
+    // anchor instead of trailing behind whatever was already there; every
+    // replica applies the same rule and so converges on the same order.
+    let mut pos = anchor;
+    while pos < cells.len() && cells[pos].after == after && cells[pos].id.agent >= id.agent {
+        pos += 1;
     }
 
-    fn pop(&mut self) -> Option<Change> {
-        if !self.is_at_head() {
-            panic!("cannot pop if not at head")
+    cells.insert(
+        pos,
+        Cell {
+            id,
+            after,
+            ch,
+            deleted: false,
+        },
+    );
+}
+
+/// A content address for a [`Change`]: the sha256 of its operation, timestamp
+/// and sorted parent hashes. Hashing the parents in sorted order means two
+/// changes built from the same parent set hash identically regardless of
+/// what order a client happened to list them in.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ChangeHash([u8; 32]);
+
+impl fmt::Debug for ChangeHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
         }
-        self.head -= 1;
-        self.changes.pop()
+        Ok(())
     }
+}
 
-    fn next(&mut self) -> Option<&Change> {
-        if self.head < self.changes.len() - 1 {
-            self.head += 1;
-            Some(&self.changes[self.head])
+/// Hex-encodes like [`Id`]'s human-readable form: a plain string on the wire,
+/// raw bytes when the format doesn't need to stay human-readable.
+impl Serialize for ChangeHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex_encode(&self.0))
         } else {
-            None
+            serializer.serialize_bytes(&self.0)
         }
     }
+}
 
-    fn previous(&mut self) -> Option<&Change> {
-        if self.head <= self.fork {
-            // cannot undo beyond what has been committed
-            None
-        } else {
-            let change = &self.changes[self.head];
-            self.head -= 1;
-            Some(&change)
-        }
+struct ChangeHashVisitor;
+
+impl<'de> de::Visitor<'de> for ChangeHashVisitor {
+    type Value = ChangeHash;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a 64 character hex-encoded sha256 hash")
     }
 
-    fn to_commit(&self) -> &[Change] {
-        &self.changes[self.fork..self.head + 1]
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        hex_decode(s).map(ChangeHash).map_err(de::Error::custom)
     }
-}
 
-fn squash_changes(changes: &[Change]) -> Vec<Change> {
-    let mut out_changes = vec![];
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        <[u8; 32]>::try_from(bytes)
+            .map(ChangeHash)
+            .map_err(|_| de::Error::invalid_length(bytes.len(), &self))
+    }
+}
 
-    for change in changes {
-        if !out_changes
-            .iter_mut()
-            .rev()
-            .any(|out_change| squash_one(out_change, change))
-        {
-            out_changes.push(change.clone());
+impl<'de> Deserialize<'de> for ChangeHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(ChangeHashVisitor)
+        } else {
+            deserializer.deserialize_bytes(ChangeHashVisitor)
         }
     }
+}
 
-    out_changes
+fn hex_encode(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
 }
 
-fn squash_one(out_change: &mut Change, change: &Change) -> bool {
-    match (&mut out_change.operation, &change.operation) {
-        (Operation::Add(out_item), Operation::Edit(old_item, new_item)) if out_item == old_item => {
-            *out_item = new_item.clone();
+fn hex_decode(s: &str) -> std::result::Result<[u8; 32], String> {
+    if s.len() != 64 {
+        return Err(format!("expected a 64 character hex string, got {}", s.len()));
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|err| err.to_string())?;
+    }
+    Ok(bytes)
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Change {
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    operation: Operation,
+    parents: Vec<ChangeHash>,
+    hash: ChangeHash,
+}
+
+impl Change {
+    fn root() -> Self {
+        Self::new(Operation::Root, vec![])
+    }
+
+    fn new(operation: Operation, parents: Vec<ChangeHash>) -> Self {
+        let timestamp = now_millis();
+        let mut sorted_parents = parents.clone();
+        sorted_parents.sort_by_key(|hash| hash.0);
+        let hash = hash_change(&operation, timestamp, &sorted_parents);
+        Self {
+            timestamp,
+            operation,
+            parents,
+            hash,
+        }
+    }
+
+    pub fn hash(&self) -> ChangeHash {
+        self.hash
+    }
+
+    pub fn parents(&self) -> &[ChangeHash] {
+        &self.parents
+    }
+
+    pub fn operation(&self) -> &Operation {
+        &self.operation
+    }
+
+    fn update_item_id(&mut self, id_map: &HashMap<Id, Id>) {
+        match &mut self.operation {
+            Operation::Remove(item) if id_map.contains_key(&item.id) => item.id = id_map[&item.id],
+            Operation::Edit(edit) if id_map.contains_key(&edit.id) => edit.id = id_map[&edit.id],
+            _ => {}
+        }
+    }
+}
+
+/// `Utc::now()` truncated to millisecond precision, so an in-memory timestamp
+/// always matches what it hashes to and what the epoch-millis wire format
+/// round-trips it to.
+fn now_millis() -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(Utc::now().timestamp_millis()).expect("now() is always in range")
+}
+
+fn hash_change(operation: &Operation, timestamp: DateTime<Utc>, sorted_parents: &[ChangeHash]) -> ChangeHash {
+    let mut hasher = Sha256::new();
+    hasher.update(operation.canonical_bytes());
+    hasher.update(timestamp.timestamp_millis().to_be_bytes());
+    for parent in sorted_parents {
+        hasher.update(parent.0);
+    }
+    ChangeHash(hasher.finalize().into())
+}
+
+impl Operation {
+    /// The id of the item this operation concerns, if any (`Root` touches no item).
+    pub fn item_id(&self) -> Option<Id> {
+        match self {
+            Operation::Root => None,
+            Operation::Add(item) | Operation::Remove(item) => Some(item.id),
+            Operation::Edit(edit) => Some(edit.id),
+        }
+    }
+
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            Operation::Root => bytes.push(0),
+            Operation::Add(item) => {
+                bytes.push(1);
+                bytes.extend(item.canonical_bytes());
+            }
+            Operation::Remove(item) => {
+                bytes.push(2);
+                bytes.extend(item.canonical_bytes());
+            }
+            Operation::Edit(edit) => {
+                bytes.push(3);
+                bytes.extend(edit.canonical_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+impl ItemEdit {
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(self.id.agent().to_be_bytes());
+        bytes.extend(self.id.id().to_be_bytes());
+        for op in &self.title_ops {
+            bytes.extend(op.canonical_bytes());
+        }
+        match self.done {
+            Some((old, new)) => bytes.extend([1, old as u8, new as u8]),
+            None => bytes.push(0),
+        }
+        match self.order {
+            Some((old, new)) => {
+                bytes.push(1);
+                bytes.extend(old.to_be_bytes());
+                bytes.extend(new.to_be_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes.push(self.undelete as u8);
+        bytes
+    }
+}
+
+impl TextOp {
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            TextOp::Insert { id, after, ch } => {
+                bytes.push(1);
+                bytes.extend(id.agent.to_be_bytes());
+                bytes.extend(id.seq.to_be_bytes());
+                match after {
+                    Some(after) => {
+                        bytes.push(1);
+                        bytes.extend(after.agent.to_be_bytes());
+                        bytes.extend(after.seq.to_be_bytes());
+                    }
+                    None => bytes.push(0),
+                }
+                bytes.extend((*ch as u32).to_be_bytes());
+            }
+            TextOp::Delete { id } => {
+                bytes.push(2);
+                bytes.extend(id.agent.to_be_bytes());
+                bytes.extend(id.seq.to_be_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+impl ListItem {
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(self.id.agent().to_be_bytes());
+        bytes.extend(self.id.id().to_be_bytes());
+        bytes.extend((self.title.len() as u32).to_be_bytes());
+        bytes.extend(self.title.as_bytes());
+        bytes.push(self.done as u8);
+        bytes.extend(self.order.to_be_bytes());
+        bytes
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChangeLog {
+    head: usize,
+    fork: usize,
+    /// A persistent, structure-sharing vector: cloning a `ChangeLog` — which
+    /// happens on every `ServerList::snapshot()` — shares the existing
+    /// history in O(1)/O(log n) instead of deep-copying it, and only the
+    /// path touched by later writes diverges. Built on `im`'s `Arc`-backed
+    /// vector rather than `im-rc`'s `Rc`-backed one so snapshots stay `Send`
+    /// across connections in [`crate::server`].
+    changes: Vector<Change>,
+    by_hash: im::HashMap<ChangeHash, Change>,
+    heads: Vec<ChangeHash>,
+}
+
+impl ChangeLog {
+    fn new() -> Self {
+        let root = Change::root();
+        let mut by_hash = im::HashMap::new();
+        by_hash.insert(root.hash(), root.clone());
+        Self {
+            head: 0,
+            fork: 0,
+            heads: vec![root.hash()],
+            changes: Vector::unit(root),
+            by_hash,
+        }
+    }
+
+    fn push(&mut self, op: Operation) -> &Change {
+        if !self.is_at_head() {
+            panic!("cannot push when not at head")
+        }
+        let change = Change::new(op, self.heads.clone());
+        self.record(change);
+        self.next().expect("just pushed one")
+    }
+
+    fn push_change(&mut self, change: &Change) -> &Change {
+        self.record(change.clone());
+        self.next().expect("just pushed one")
+    }
+
+    /// Appends `change` to the linear history and updates the hash index/heads.
+    fn record(&mut self, change: Change) {
+        self.heads.retain(|head| !change.parents.contains(head));
+        if !self.heads.contains(&change.hash) {
+            self.heads.push(change.hash);
+        }
+        self.by_hash.insert(change.hash, change.clone());
+        self.changes.push_back(change);
+    }
+
+    /// The hashes of changes that currently have no known children.
+    fn heads(&self) -> Vec<ChangeHash> {
+        self.heads.clone()
+    }
+
+    /// Whether a change with this hash has already been recorded, making a
+    /// re-commit of it a no-op.
+    fn contains_hash(&self, hash: &ChangeHash) -> bool {
+        self.by_hash.contains_key(hash)
+    }
+
+    fn changes_since(&self, change: &Change) -> Vec<Change> {
+        if let Some(i) = self.changes.iter().position(|c| c == change) {
+            self.changes.iter().skip(i + 1).cloned().collect()
+        } else {
+            unreachable!("the change must exist!")
+        }
+    }
+
+    /// The changes recorded after the latest of `their_heads` in this log's
+    /// linear history, in recorded order (so every change appears after its
+    /// parents); if none of `their_heads` is known, every change but the root.
+    ///
+    /// TODO: this assumes a single linear history per `List`; a real DAG
+    /// would need a proper topological sort over `by_hash`.
+    fn missing_since(&self, their_heads: &[ChangeHash]) -> Vec<Change> {
+        let start = their_heads
+            .iter()
+            .filter_map(|hash| self.changes.iter().position(|c| c.hash == *hash))
+            .max()
+            .map(|i| i + 1)
+            .unwrap_or(1); // unknown heads: send everything but the root
+        self.changes.iter().skip(start).cloned().collect()
+    }
+
+    fn is_at_head(&self) -> bool {
+        // TODO: this means the fork change is included in changes_to_commit
+        self.head == self.changes.len() - 1
+    }
+
+    fn pop(&mut self) -> Option<Change> {
+        if !self.is_at_head() {
+            panic!("cannot pop if not at head")
+        }
+        self.head -= 1;
+        let change = self.changes.pop_back();
+        if let Some(change) = &change {
+            self.by_hash.remove(&change.hash);
+            self.heads.retain(|head| *head != change.hash);
+            self.heads.extend(change.parents.iter().copied());
+        }
+        change
+    }
+
+    fn next(&mut self) -> Option<&Change> {
+        if self.head < self.changes.len() - 1 {
+            self.head += 1;
+            Some(&self.changes[self.head])
+        } else {
+            None
+        }
+    }
+
+    fn previous(&mut self) -> Option<&Change> {
+        if self.head <= self.fork {
+            // cannot undo beyond what has been committed
+            None
+        } else {
+            let change = &self.changes[self.head];
+            self.head -= 1;
+            Some(&change)
+        }
+    }
+
+    fn to_commit(&self) -> Vec<Change> {
+        self.changes
+            .iter()
+            .skip(self.fork)
+            .take(self.head + 1 - self.fork)
+            .cloned()
+            .collect()
+    }
+}
+
+/// The result of [`topo_order_by_parents`]: `resolved` is every change whose
+/// ancestry is fully accounted for, in apply order; `unresolved` is whatever
+/// still references a parent hash nobody's seen yet, verbatim and in no
+/// particular order.
+struct TopoOrder {
+    resolved: Vec<Change>,
+    unresolved: Vec<Change>,
+}
+
+/// Orders `changes` so that every change comes after all of its parents,
+/// given the changes already known from `known`. A change whose parents
+/// never resolve against `known` or each other is *not* ordered alongside
+/// the rest — applying it now would apply an edit whose logical predecessor
+/// never landed — so it comes back out in `unresolved` for the caller to
+/// hold and retry once more history has arrived.
+///
+/// TODO: this is a small bounded sort suitable for one client's batch; a
+/// general multi-source DAG would want a proper Kahn's-algorithm pass.
+fn topo_order_by_parents(known: &im::HashMap<ChangeHash, Change>, changes: Vec<Change>) -> TopoOrder {
+    let mut known_hashes: std::collections::HashSet<ChangeHash> = known.keys().copied().collect();
+    let mut pending = changes;
+    let mut ordered = Vec::with_capacity(pending.len());
+    loop {
+        let mut progressed = false;
+        pending.retain(|change| {
+            if change.parents.iter().all(|p| known_hashes.contains(p)) {
+                known_hashes.insert(change.hash);
+                ordered.push(change.clone());
+                progressed = true;
+                false
+            } else {
+                true
+            }
+        });
+        if pending.is_empty() || !progressed {
+            break;
+        }
+    }
+    TopoOrder { resolved: ordered, unresolved: pending }
+}
+
+fn squash_changes(changes: &[Change]) -> Vec<Change> {
+    let mut out_changes = vec![];
+
+    for change in changes {
+        if !out_changes
+            .iter_mut()
+            .rev()
+            .any(|out_change| squash_one(out_change, change))
+        {
+            out_changes.push(change.clone());
+        }
+    }
+
+    out_changes
+}
+
+fn squash_one(out_change: &mut Change, change: &Change) -> bool {
+    match (&mut out_change.operation, &change.operation) {
+        (Operation::Add(out_item), Operation::Edit(edit)) if out_item.id == edit.id => {
+            apply_text_ops(&mut out_item.title_cells, &edit.title_ops);
+            out_item.title = render_title(&out_item.title_cells);
+            if let Some((_, done)) = edit.done {
+                out_item.done = done;
+            }
+            if let Some((_, order)) = edit.order {
+                out_item.order = order;
+            }
             true
         }
         (_, _) => false,
@@ -533,12 +1665,15 @@ fn transform(confirmed_changes: &[Change], incoming_changes: &[Change]) -> Vec<C
         match transform_one(confirmed_changes, &incoming_change) {
             TransformResult::Apply(change) => new_changes.push(change),
             TransformResult::Skip(conflicting_change) => {
-                if let (
-                    Operation::Add(incoming_item),
-                    Operation::Add(existing_item) | Operation::Edit(existing_item, _),
-                ) = (incoming_change.operation, conflicting_change.operation)
-                {
-                    id_map.insert(incoming_item.id, existing_item.id);
+                if let Operation::Add(incoming_item) = incoming_change.operation {
+                    let existing_id = match conflicting_change.operation {
+                        Operation::Add(existing_item) => Some(existing_item.id),
+                        Operation::Edit(existing_edit) => Some(existing_edit.id),
+                        _ => None,
+                    };
+                    if let Some(existing_id) = existing_id {
+                        id_map.insert(incoming_item.id, existing_id);
+                    }
                 }
             }
         }
@@ -559,51 +1694,45 @@ fn transform_one(confirmed_changes: &[Change], incoming_change: &Change) -> Tran
                     return TransformResult::Skip(confirmed_change.clone());
                 }
             }
-            (Add(confirmed_item), Edit(incoming_item, _)) => {
-                if confirmed_item.id == incoming_item.id {
-                    // can happen if ids have been mapped from a skipped add
-                    if confirmed_item.title != incoming_item.title {
-                        // TODO: create a new item item for the edit
-                        //  - how to calculate new ID? Use UUIDs?
-                        //  - how to address duplicate titles? let it bubble up to the user?
-                        todo!("this is hard to decide")
-                    } else {
-                        return TransformResult::Apply(incoming_change.clone());
-                    }
+            (Add(confirmed_item), Edit(incoming_edit)) => {
+                if confirmed_item.id == incoming_edit.id {
+                    // Title edits are character-level merges keyed by cell id
+                    // (see `apply_text_ops`), so there is no need for the
+                    // confirmed and incoming titles to agree on a common base
+                    // before applying: the cell ops carry that context
+                    // themselves, even across a rename.
+                    return TransformResult::Apply(incoming_change.clone());
                 }
             }
-            (Edit(confirmed_item, confirmed_new_item), Add(incoming_item)) => {
-                if confirmed_item.id == incoming_item.id {
+            (Edit(confirmed_edit), Add(incoming_item)) => {
+                if confirmed_edit.id == incoming_item.id {
                     // replaying changes should be filtered by id earlier
                     unreachable!("cannot add what is already edited")
-                } else if incoming_item.title == confirmed_new_item.title {
+                } else if incoming_item.title == confirmed_edit.resulting_title {
                     return TransformResult::Skip(confirmed_change.clone());
                 }
             }
-            (Edit(confirmed_item, confirmed_new_item), Edit(incoming_item, incoming_new_item)) => {
-                if confirmed_item.id == incoming_item.id {
-                    if confirmed_item.title == incoming_item.title {
-                        if confirmed_new_item.title == incoming_new_item.title {
-                            // last write wins
-                            return TransformResult::Apply(incoming_change.clone());
-                        } else {
-                            // TODO: should we create a new item? how to handle ids?
-                            todo!("this is hard to decide")
-                        }
-                    } else {
-                        todo!("multiple edits, hard to decide")
-                    }
+            (Edit(confirmed_edit), Edit(incoming_edit)) => {
+                if confirmed_edit.id == incoming_edit.id {
+                    // Concurrent renames merge character-by-character instead
+                    // of one clobbering the other.
+                    return TransformResult::Apply(incoming_change.clone());
                 }
             }
-            (Remove(confirmed_item), Remove(incoming_item)) => {
-                if confirmed_item.id == incoming_item.id {
-                    return TransformResult::Skip(confirmed_change.clone());
-                }
+            (Remove(_), Remove(_)) => {
+                // Unlike the other ops, a repeated `Remove` for the same id
+                // isn't dead weight to skip: `apply`'s `Remove` arm is
+                // idempotent against an already-deleted item, but it's also
+                // how a resolved [`ConflictValues::Deleted`] gets confirmed,
+                // so it always needs to go through.
             }
-            (Remove(confirmed_item), Edit(incoming_item, _)) => {
-                if confirmed_item.id == incoming_item.id {
-                    // TODO: should we add a new item? how to handle ids?
-                    todo!("this is hard to decide")
+            (Remove(confirmed_item), Edit(incoming_edit)) => {
+                if confirmed_item.id == incoming_edit.id {
+                    // Let it through rather than deciding here: `apply`'s
+                    // `Edit` arm already knows how to settle a delete-vs-edit
+                    // clash (tombstone stays, a `ConflictValues::Deleted` is
+                    // raised unless the edit carries `undelete`).
+                    return TransformResult::Apply(incoming_change.clone());
                 }
             }
             (Remove(_), Add(_)) => {}
@@ -613,7 +1742,7 @@ fn transform_one(confirmed_changes: &[Change], incoming_change: &Change) -> Tran
                     return TransformResult::Apply(incoming_change.clone());
                 }
             }
-            (Edit(_, _), Remove(_)) => {}
+            (Edit(_), Remove(_)) => {}
             (Root, _) => {}
             (_, Root) => unreachable!("root is always confirmed"),
         }
@@ -702,13 +1831,67 @@ mod tests {
             let mut list = server.snapshot();
             list.add("potatoes");
             let changes_in = list.changes_to_commit();
-            let changes_out = server.commit(changes_in).unwrap();
+            let changes_out = server.commit(&changes_in).unwrap();
+            assert_eq!(changes_out.len(), 1);
+
+            let list = server.snapshot();
+            assert_eq!(list_titles(&list), vec!["potatoes"]);
+        }
+
+        #[test]
+        fn recommitting_the_same_changes_is_a_no_op() {
+            let mut server = ServerList::new();
+            let mut list = server.snapshot();
+            list.add("potatoes");
+            let changes_in = list.changes_to_commit().to_vec();
+
+            let changes_out = server.commit(&changes_in).unwrap();
             assert_eq!(changes_out.len(), 1);
 
+            let changes_out_again = server.commit(&changes_in).unwrap();
+            assert!(changes_out_again.is_empty());
+
             let list = server.snapshot();
             assert_eq!(list_titles(&list), vec!["potatoes"]);
         }
 
+        #[test]
+        fn changes_missing_returns_only_what_a_client_lacks() {
+            let mut server = ServerList::new();
+            let mut list1 = server.snapshot();
+            list1.add("potatoes");
+            server.commit(&list1.changes_to_commit()).unwrap();
+            let client_heads = server.heads();
+
+            let mut list2 = server.snapshot();
+            list2.add("tomatoes");
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let missing = server.changes_missing(&client_heads);
+            assert_eq!(missing.len(), 1);
+        }
+
+        #[test]
+        fn commit_buffers_a_change_with_an_unknown_parent_instead_of_applying_it() {
+            let mut server = ServerList::new();
+            let mut list = server.snapshot();
+            let anchor = list.changes_to_commit().into_iter().next().unwrap();
+            server.commit(&[anchor.clone()]).unwrap();
+
+            let mut list = server.snapshot();
+            list.add("potatoes");
+            let mut orphan = list.changes_to_commit().into_iter().last().unwrap();
+            // Point the change at a parent hash the server has never seen,
+            // as if an earlier change in its history got lost in transit.
+            orphan.parents = vec![ChangeHash([0xff; 32])];
+
+            let confirmed = server.commit(&[anchor, orphan]).unwrap();
+            assert!(confirmed.is_empty());
+
+            let list = server.snapshot();
+            assert!(list_titles(&list).is_empty());
+        }
+
         #[test]
         fn commit_adds() {
             let mut server = ServerList::new();
@@ -716,9 +1899,9 @@ mod tests {
             let mut list2 = server.snapshot();
             list1.add("potatoes");
             list2.add("tomatoes");
-            let changes_out1 = server.commit(list1.changes_to_commit()).unwrap();
+            let changes_out1 = server.commit(&list1.changes_to_commit()).unwrap();
             assert_eq!(changes_out1.len(), 1);
-            let changes_out2 = server.commit(list2.changes_to_commit()).unwrap();
+            let changes_out2 = server.commit(&list2.changes_to_commit()).unwrap();
             assert_eq!(changes_out2.len(), 2);
 
             let list = server.snapshot();
@@ -746,8 +1929,8 @@ mod tests {
             list1.add("apples");
             list2.add("apples");
 
-            let changes1 = server.commit(list1.changes_to_commit()).unwrap();
-            let changes2 = server.commit(list2.changes_to_commit()).unwrap();
+            let changes1 = server.commit(&list1.changes_to_commit()).unwrap();
+            let changes2 = server.commit(&list2.changes_to_commit()).unwrap();
 
             assert_eq!(changes1, changes2);
         }
@@ -763,8 +1946,8 @@ mod tests {
                 .update(item, UpdateListItem::new().title("beans"))
                 .unwrap();
 
-            let changes1 = server.commit(list1.changes_to_commit()).unwrap();
-            let changes2 = server.commit(list2.changes_to_commit()).unwrap();
+            let changes1 = server.commit(&list1.changes_to_commit()).unwrap();
+            let changes2 = server.commit(&list2.changes_to_commit()).unwrap();
 
             assert_eq!(changes1.len(), 1);
             assert_eq!(changes2.len(), 2);
@@ -783,8 +1966,8 @@ mod tests {
                 .unwrap();
             list2.add("beans");
 
-            let changes1 = server.commit(list1.changes_to_commit()).unwrap();
-            let changes2 = server.commit(list2.changes_to_commit()).unwrap();
+            let changes1 = server.commit(&list1.changes_to_commit()).unwrap();
+            let changes2 = server.commit(&list2.changes_to_commit()).unwrap();
 
             assert_eq!(changes1, changes2);
         }
@@ -805,8 +1988,8 @@ mod tests {
                 .update(item, UpdateListItem::new().title("apples"))
                 .unwrap();
 
-            let changes1 = server.commit(list1.changes_to_commit()).unwrap();
-            let changes2 = server.commit(list2.changes_to_commit()).unwrap();
+            let changes1 = server.commit(&list1.changes_to_commit()).unwrap();
+            let changes2 = server.commit(&list2.changes_to_commit()).unwrap();
 
             assert_eq!(changes1.len(), 1);
             assert_eq!(changes2.len(), 2);
@@ -815,6 +1998,716 @@ mod tests {
         }
     }
 
+    mod field_merge {
+        use super::*;
+
+        #[test]
+        fn concurrent_edits_to_different_fields_both_apply() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+
+            list1
+                .update(item.clone(), UpdateListItem::new().title("pears"))
+                .unwrap();
+            list2.update(item, UpdateListItem::new().tick()).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert_eq!(list_titles(&server.list), vec!["pears"]);
+            assert!(server.list.items[0].done);
+        }
+
+        #[test]
+        fn server_unchanged_field_takes_the_incoming_edit() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+
+            list1
+                .update(item.clone(), UpdateListItem::new().title("pears"))
+                .unwrap();
+            list2.update(item, UpdateListItem::new().order(5.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert_eq!(server.list.items[0].order, 5.0);
+        }
+
+        #[test]
+        fn genuinely_conflicting_field_edits_keep_the_servers_value() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+
+            list1
+                .update(item.clone(), UpdateListItem::new().order(2.0))
+                .unwrap();
+            list2.update(item, UpdateListItem::new().order(3.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            // base 1.0, server moved to 2.0, incoming wanted 3.0: all three
+            // differ, so the server's already-confirmed value is kept.
+            assert_eq!(server.list.items[0].order, 2.0);
+        }
+    }
+
+    mod conflicts {
+        use super::*;
+
+        #[test]
+        fn a_genuine_conflict_is_materialized_rather_than_dropped() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+
+            list1
+                .update(item.clone(), UpdateListItem::new().order(2.0))
+                .unwrap();
+            list2.update(item, UpdateListItem::new().order(3.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert_eq!(
+                server.list.conflicts(),
+                &[Conflict {
+                    id: server.list.items[0].id,
+                    values: ConflictValues::Order {
+                        base: 1.0,
+                        values: (2.0, 3.0),
+                    },
+                }]
+            );
+        }
+
+        #[test]
+        fn resolving_a_conflict_commits_the_chosen_value_and_clears_it() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+
+            list1
+                .update(item.clone(), UpdateListItem::new().order(2.0))
+                .unwrap();
+            list2.update(item, UpdateListItem::new().order(3.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let id = server.list.items[0].id;
+            let mut resolving = server.snapshot();
+            resolving.resolve(id, Resolution::Order(3.0)).unwrap();
+            server.commit(&resolving.changes_to_commit()).unwrap();
+
+            assert!(server.list.conflicts().is_empty());
+            assert_eq!(server.list.items[0].order, 3.0);
+        }
+
+        #[test]
+        fn resolving_a_field_with_no_conflict_errors() {
+            let mut list = ServerList::new().snapshot();
+            let item = list.add("apples");
+            assert!(list.resolve(item.id, Resolution::Order(9.0)).is_err());
+        }
+    }
+
+    mod merge_strategy {
+        use super::*;
+        use std::thread::sleep;
+        use std::time::Duration;
+
+        #[test]
+        fn manual_is_the_default() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+            list2.update(item, UpdateListItem::new().order(3.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert_eq!(server.list.items[0].order, 2.0);
+            assert_eq!(server.list.conflicts().len(), 1);
+        }
+
+        #[test]
+        fn prefer_server_keeps_the_servers_value_and_raises_no_conflict() {
+            let mut server = ServerList::new().with_merge_strategy(MergeStrategy::PreferServer);
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+            list2.update(item, UpdateListItem::new().order(3.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert_eq!(server.list.items[0].order, 2.0);
+            assert!(server.list.conflicts().is_empty());
+        }
+
+        #[test]
+        fn prefer_incoming_takes_the_committing_edits_value() {
+            let mut server = ServerList::new().with_merge_strategy(MergeStrategy::PreferIncoming);
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+            list2.update(item, UpdateListItem::new().order(3.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert_eq!(server.list.items[0].order, 3.0);
+            assert!(server.list.conflicts().is_empty());
+        }
+
+        #[test]
+        fn prefer_latest_takes_the_more_recently_created_edit() {
+            let mut server = ServerList::new().with_merge_strategy(MergeStrategy::PreferLatest);
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            list1.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+
+            sleep(Duration::from_millis(2));
+
+            let mut list2 = server.snapshot();
+            list2.update(item, UpdateListItem::new().order(3.0)).unwrap();
+
+            // list1 commits first, but list2's edit was created later.
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert_eq!(server.list.items[0].order, 3.0);
+        }
+
+        #[test]
+        fn prefer_latest_keeps_the_server_when_its_value_is_newer() {
+            let mut server = ServerList::new().with_merge_strategy(MergeStrategy::PreferLatest);
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut stale = server.snapshot();
+            stale.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+
+            sleep(Duration::from_millis(2));
+
+            let mut fresh = server.snapshot();
+            fresh.update(item, UpdateListItem::new().order(5.0)).unwrap();
+            server.commit(&fresh.changes_to_commit()).unwrap();
+
+            // stale's edit was created before the server's own value was last
+            // set, so it loses even though it's committed last.
+            server.commit(&stale.changes_to_commit()).unwrap();
+
+            assert_eq!(server.list.items[0].order, 5.0);
+        }
+    }
+
+    mod delete_matrix {
+        use super::*;
+
+        #[test]
+        fn concurrent_deletes_of_the_same_item_are_clean() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.remove(item.clone()).unwrap();
+            list2.remove(item.clone()).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert!(server.list.conflicts().is_empty());
+            assert_eq!(server.list.iter().count(), 0);
+        }
+
+        #[test]
+        fn a_delete_followed_by_an_edit_is_a_conflict_and_keeps_the_item_hidden() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.remove(item.clone()).unwrap();
+            list2.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let conflicts = server.list.conflicts();
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(conflicts[0].id, item.id);
+            assert!(matches!(&conflicts[0].values, ConflictValues::Deleted { edit: Some(_) }));
+
+            // the losing edit never actually landed, so the item stays
+            // hidden with its pre-edit value until the conflict is resolved.
+            assert_eq!(server.list.iter().count(), 0);
+            assert_eq!(server.list.items[0].order, 1.0);
+        }
+
+        #[test]
+        fn an_edit_followed_by_a_delete_is_a_conflict_and_keeps_the_item_visible() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+            list2.remove(item.clone()).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let conflicts = server.list.conflicts();
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(conflicts[0].id, item.id);
+            assert!(matches!(&conflicts[0].values, ConflictValues::Deleted { edit: None }));
+
+            // the delete's snapshot was stale, so the edit it would have
+            // clobbered is kept live until the conflict is resolved.
+            assert_eq!(server.list.iter().count(), 1);
+            assert_eq!(server.list.items[0].order, 2.0);
+        }
+
+        #[test]
+        fn keep_edited_replays_the_edit_that_lost_to_a_delete() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.remove(item.clone()).unwrap();
+            list2.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let mut resolving = server.snapshot();
+            resolving.resolve(item.id, Resolution::KeepEdited).unwrap();
+            server.commit(&resolving.changes_to_commit()).unwrap();
+
+            assert!(server.list.conflicts().is_empty());
+            assert_eq!(server.list.iter().count(), 1);
+            assert_eq!(server.list.items[0].order, 2.0);
+        }
+
+        #[test]
+        fn confirm_delete_keeps_the_item_gone_after_a_delete_vs_edit_conflict() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.remove(item.clone()).unwrap();
+            list2.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let mut resolving = server.snapshot();
+            resolving.resolve(item.id, Resolution::ConfirmDelete).unwrap();
+            server.commit(&resolving.changes_to_commit()).unwrap();
+
+            assert!(server.list.conflicts().is_empty());
+            assert_eq!(server.list.iter().count(), 0);
+        }
+
+        #[test]
+        fn keep_edited_is_a_noop_but_clears_an_edit_vs_delete_conflict() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+            list2.remove(item.clone()).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let mut resolving = server.snapshot();
+            resolving.resolve(item.id, Resolution::KeepEdited).unwrap();
+            server.commit(&resolving.changes_to_commit()).unwrap();
+
+            assert!(server.list.conflicts().is_empty());
+            assert_eq!(server.list.iter().count(), 1);
+            assert_eq!(server.list.items[0].order, 2.0);
+        }
+
+        #[test]
+        fn confirm_delete_performs_the_delete_after_an_edit_vs_delete_conflict() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let item = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.update(item.clone(), UpdateListItem::new().order(2.0)).unwrap();
+            list2.remove(item.clone()).unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let mut resolving = server.snapshot();
+            resolving.resolve(item.id, Resolution::ConfirmDelete).unwrap();
+            server.commit(&resolving.changes_to_commit()).unwrap();
+
+            assert!(server.list.conflicts().is_empty());
+            assert_eq!(server.list.iter().count(), 0);
+        }
+
+        #[test]
+        fn adding_an_item_is_unaffected_by_an_unrelated_concurrent_delete() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let apples = setup.add("apples");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+            list1.remove(apples.clone()).unwrap();
+            list2.add("oranges");
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert!(server.list.conflicts().is_empty());
+            let titles: Vec<&str> = server.list.iter().map(|item| item.title.as_str()).collect();
+            assert_eq!(titles, vec!["oranges"]);
+        }
+    }
+
+    mod title_merge {
+        use super::*;
+
+        #[test]
+        fn concurrent_renames_of_the_same_item_merge_character_by_character() {
+            let mut server = ServerList::new();
+            let mut setup = server.snapshot();
+            let cat = setup.add("cat");
+            server.commit(&setup.changes_to_commit()).unwrap();
+
+            let mut list1 = server.snapshot();
+            let mut list2 = server.snapshot();
+
+            list1
+                .update(cat.clone(), UpdateListItem::new().title("cats"))
+                .unwrap();
+            list2
+                .update(cat, UpdateListItem::new().title("chat"))
+                .unwrap();
+
+            server.commit(&list1.changes_to_commit()).unwrap();
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            // neither rename clobbers the other: both edits land in the merge
+            assert_eq!(list_titles(&server.list), vec!["chats"]);
+        }
+
+        #[test]
+        fn diff_title_ops_is_a_pure_prefix_suffix_diff() {
+            let mut seq = 0;
+            let cells = new_title_cells(1, &mut seq, "apple");
+
+            let ops = diff_title_ops(&cells, "apples", 1, &mut seq);
+            assert_eq!(ops.len(), 1);
+            assert!(matches!(ops[0], TextOp::Insert { ch: 's', .. }));
+
+            let mut merged = cells.clone();
+            apply_text_ops(&mut merged, &ops);
+            assert_eq!(render_title(&merged), "apples");
+        }
+    }
+
+    mod wire {
+        use super::*;
+
+        fn round_trip(change: &Change) -> Change {
+            let json = serde_json::to_string(change).unwrap();
+            serde_json::from_str(&json).unwrap()
+        }
+
+        #[test]
+        fn root_operation_round_trips() {
+            let change = Change::root();
+            assert_eq!(round_trip(&change), change);
+        }
+
+        #[test]
+        fn add_operation_round_trips() {
+            let mut list = ServerList::new().snapshot();
+            list.add("potatoes");
+            let change = list.changes_to_commit().into_iter().last().unwrap();
+            assert_eq!(&round_trip(&change), &change);
+        }
+
+        #[test]
+        fn remove_operation_round_trips() {
+            let mut list = ServerList::new().snapshot();
+            let potatoes = list.add("potatoes");
+            list.remove(potatoes).unwrap();
+            let change = list.changes_to_commit().into_iter().last().unwrap();
+            assert_eq!(&round_trip(&change), &change);
+        }
+
+        #[test]
+        fn edit_operation_round_trips() {
+            let mut list = ServerList::new().snapshot();
+            let potatoes = list.add("potatoes");
+            list.update(potatoes, UpdateListItem::new().title("potato"))
+                .unwrap();
+            let change = list.changes_to_commit().into_iter().last().unwrap();
+            assert_eq!(&round_trip(&change), &change);
+        }
+
+        #[test]
+        fn operation_is_flattened_into_the_change_not_nested_under_it() {
+            let mut list = ServerList::new().snapshot();
+            list.add("potatoes");
+            let change = list.changes_to_commit().into_iter().last().unwrap();
+            let value: serde_json::Value = serde_json::to_value(&change).unwrap();
+            let object = value.as_object().unwrap();
+
+            // "operation" and "item" sit beside "timestamp"/"parents"/"hash"
+            // at the top level, as the module doc comment shows, rather than
+            // under a nested `"operation": {"operation": "add", "item": {...}}`.
+            assert_eq!(object["operation"], "add");
+            assert!(object["item"].is_object());
+            assert!(object.get("timestamp").is_some());
+        }
+
+        #[test]
+        fn timestamp_is_encoded_as_epoch_millis() {
+            let change = Change::root();
+            let json = serde_json::to_string(&change).unwrap();
+            let expected = format!(r#""timestamp":{}"#, change.timestamp.timestamp_millis());
+            assert!(json.contains(&expected));
+        }
+
+        #[test]
+        fn sync_message_round_trips_through_json() {
+            let mut server = ServerList::new();
+            let mut list = server.snapshot();
+            list.add("potatoes");
+            let message = list.to_sync_message();
+
+            let json = message.to_json().unwrap();
+            let decoded = SyncMessage::from_json(&json).unwrap();
+            assert_eq!(decoded, message);
+        }
+
+        #[test]
+        fn commit_message_confirms_changes_in_the_same_envelope() {
+            let mut server = ServerList::new();
+            let mut list = server.snapshot();
+            list.add("potatoes");
+            let message = list.to_sync_message();
+
+            let confirmed = server.commit_message(&message).unwrap();
+            assert_eq!(confirmed.fork, message.fork);
+            assert_eq!(confirmed.changes.len(), 1);
+        }
+
+        #[test]
+        fn commit_rejects_an_empty_changes_list_instead_of_panicking() {
+            let mut server = ServerList::new();
+            let message = SyncMessage { fork: 0, changes: vec![] };
+            assert!(server.commit_message(&message).is_err());
+        }
+    }
+
+    mod storage {
+        use super::*;
+        use crate::store::FileChangeStore;
+
+        #[test]
+        fn reopening_a_store_recovers_the_pre_crash_state() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("changes.log");
+
+            let mut server = ServerList::open(FileChangeStore::open(&path)).unwrap();
+            let mut list = server.snapshot();
+            list.add("potatoes");
+            list.add("tomatoes");
+            server.commit(&list.changes_to_commit()).unwrap();
+
+            // the "crash": drop the in-memory server and reopen from the same store
+            drop(server);
+            let mut recovered = ServerList::open(FileChangeStore::open(&path)).unwrap();
+
+            assert_eq!(
+                list_titles(&recovered.snapshot()),
+                vec!["potatoes", "tomatoes"]
+            );
+        }
+
+        #[test]
+        fn recovered_agent_ids_do_not_collide_with_history() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("changes.log");
+
+            let mut server = ServerList::open(FileChangeStore::open(&path)).unwrap();
+            let mut list = server.snapshot();
+            let used_agent_id = list.agent_id();
+            list.add("potatoes");
+            server.commit(&list.changes_to_commit()).unwrap();
+            drop(server);
+
+            let mut recovered = ServerList::open(FileChangeStore::open(&path)).unwrap();
+            let new_agent_id = recovered.snapshot().agent_id();
+
+            assert!(new_agent_id > used_agent_id);
+        }
+    }
+
+    mod undo_redo {
+        use super::*;
+
+        #[test]
+        fn operations_are_logged_with_parent_links_in_commit_order() {
+            let mut server = ServerList::new();
+            let mut list1 = server.snapshot();
+            list1.add("potatoes");
+            server.commit(&list1.changes_to_commit()).unwrap();
+
+            let mut list2 = server.snapshot();
+            list2.add("tomatoes");
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let ops = server.operations();
+            assert_eq!(ops.len(), 2);
+            assert_eq!(ops[0].parent, None);
+            assert_eq!(ops[1].parent, Some(ops[0].id));
+        }
+
+        #[test]
+        fn undoing_the_latest_operation_reverts_its_changes() {
+            let mut server = ServerList::new();
+            let mut list1 = server.snapshot();
+            list1.add("potatoes");
+            server.commit(&list1.changes_to_commit()).unwrap();
+
+            let mut list2 = server.snapshot();
+            list2.add("tomatoes");
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            let latest = server.operations().last().unwrap().id;
+            server.undo(latest).unwrap();
+
+            assert_eq!(list_titles(&server.list), vec!["potatoes"]);
+            assert_eq!(server.operations().len(), 1);
+        }
+
+        #[test]
+        fn redo_restores_an_undone_operation() {
+            let mut server = ServerList::new();
+            let mut list1 = server.snapshot();
+            list1.add("potatoes");
+            server.commit(&list1.changes_to_commit()).unwrap();
+
+            let latest = server.operations().last().unwrap().id;
+            server.undo(latest).unwrap();
+            assert_eq!(list_titles(&server.list), Vec::<&str>::new());
+
+            server.redo().unwrap();
+            assert_eq!(list_titles(&server.list), vec!["potatoes"]);
+            assert_eq!(server.operations().len(), 1);
+        }
+
+        #[test]
+        fn redo_after_a_new_commit_is_rejected() {
+            let mut server = ServerList::new();
+            let mut list1 = server.snapshot();
+            list1.add("potatoes");
+            server.commit(&list1.changes_to_commit()).unwrap();
+
+            let latest = server.operations().last().unwrap().id;
+            server.undo(latest).unwrap();
+
+            let mut list2 = server.snapshot();
+            list2.add("tomatoes");
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            assert!(server.redo().is_err());
+        }
+
+        #[test]
+        fn undoing_an_earlier_operation_rebases_later_ones_on_top() {
+            let mut server = ServerList::new();
+            let mut list1 = server.snapshot();
+            list1.add("potatoes");
+            server.commit(&list1.changes_to_commit()).unwrap();
+            let first = server.operations()[0].id;
+
+            let mut list2 = server.snapshot();
+            list2.add("tomatoes");
+            server.commit(&list2.changes_to_commit()).unwrap();
+
+            server.undo(first).unwrap();
+
+            // only "potatoes" (the targeted operation) disappears; the later
+            // "tomatoes" commit is rebased back on top rather than lost.
+            assert_eq!(list_titles(&server.list), vec!["tomatoes"]);
+            assert_eq!(server.operations().len(), 1);
+        }
+    }
+
     fn list_titles(list: &List) -> Vec<&str> {
         list.iter().map(|item| item.title.as_str()).collect()
     }
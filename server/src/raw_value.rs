@@ -0,0 +1,72 @@
+//! An opaque, passthrough value: captures the raw bytes of whatever JSON was
+//! deserialized and re-emits them verbatim on serialization, without ever
+//! parsing or validating the contents.
+
+use std::fmt;
+
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue as JsonRawValue;
+
+#[derive(Clone)]
+pub struct RawValue(Box<JsonRawValue>);
+
+impl RawValue {
+    /// The raw, unparsed JSON text this value was deserialized from.
+    pub fn get(&self) -> &str {
+        self.0.get()
+    }
+}
+
+impl fmt::Debug for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RawValue({})", self.get())
+    }
+}
+
+impl PartialEq for RawValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl Serialize for RawValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Box::<JsonRawValue>::deserialize(deserializer).map(RawValue)
+    }
+}
+
+impl JsonSchema for RawValue {
+    fn schema_name() -> String {
+        "RawValue".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // Any JSON value is valid: we never look inside it.
+        Schema::Bool(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_verbatim() {
+        let raw: RawValue = serde_json::from_str(r#"{"b":1,"a":2}"#).unwrap();
+        assert_eq!(raw.get(), r#"{"b":1,"a":2}"#);
+        assert_eq!(serde_json::to_string(&raw).unwrap(), r#"{"b":1,"a":2}"#);
+    }
+}
@@ -0,0 +1,375 @@
+//! A plain CRUD surface around named [`List`]s, as distinct from
+//! [`crate::server`]'s OT sync transport: each list here is mutated directly
+//! through ordinary request/response handlers rather than commit/transform,
+//! so there's no concept of a client falling behind to catch up on. Each
+//! list also fans mutations out as [`ListEvent`]s to anyone watching
+//! `GET /lists/:name/events`, for push-based clients that don't want to poll.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::{delete, get, post, MethodRouter};
+use axum::{Json, Router};
+use futures::{Stream, StreamExt};
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::id::Id;
+use crate::lists::{List, ListItem, ServerList};
+use crate::storage::Storage;
+
+/// A mutation to a list, fanned out to `GET /lists/:name/events` subscribers
+/// after the CRUD handler that caused it has applied it.
+#[derive(Serialize, JsonSchema, Clone, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ListEvent {
+    ItemAdded { item: ListItem },
+    ItemRemoved { id: Id },
+    ListCleared,
+}
+
+/// One registry entry: the list itself, plus the broadcast channel its
+/// mutations are announced on. Kept as a pair rather than two parallel maps
+/// so a single lookup finds both.
+struct Entry {
+    list: List,
+    events: broadcast::Sender<ListEvent>,
+}
+
+impl Entry {
+    fn new(list: List) -> Self {
+        let (events, _) = broadcast::channel(16);
+        Self { list, events }
+    }
+
+    fn from_titles(titles: Vec<String>) -> Self {
+        let mut list = ServerList::new().snapshot();
+        for title in titles {
+            list.add(title);
+        }
+        Self::new(list)
+    }
+}
+
+/// The titles of a list's live items, in display order — what gets persisted
+/// to [`Storage`] after every mutation.
+fn titles(list: &List) -> Vec<String> {
+    list.items().map(|item| item.title.clone()).collect()
+}
+
+/// Named lists behind a single lock, backed by [`Storage`] for durability:
+/// reads take a brief read-lock just long enough to clone the one list (or
+/// subscribe to the one channel) they need, so the lock is never held across
+/// a handler's own work; writes take the write-lock, mutate the stored list
+/// in place, then write the list's new item titles through to storage.
+#[derive(Clone)]
+pub struct ListStore {
+    lists: Arc<RwLock<HashMap<String, Entry>>>,
+    storage: Arc<Storage>,
+}
+
+impl Default for ListStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListStore {
+    /// A store with no durability: mutations are lost on restart. Mainly
+    /// useful for tests.
+    pub fn new() -> Self {
+        Self::with_storage(Storage::in_memory())
+    }
+
+    fn with_storage(storage: Storage) -> Self {
+        Self {
+            lists: Arc::new(RwLock::new(HashMap::new())),
+            storage: Arc::new(storage),
+        }
+    }
+
+    /// Opens a store backed by `storage`, replaying every list already
+    /// persisted there into a fresh in-memory [`List`] — the startup path
+    /// for a real deployment (see [`crate::cli::run`]).
+    pub fn open(storage: Storage) -> anyhow::Result<Self> {
+        let store = Self::with_storage(storage);
+        let mut locked = store.lists.write().unwrap();
+        for (name, titles) in store.storage.load_all()? {
+            locked.insert(name, Entry::from_titles(titles));
+        }
+        drop(locked);
+        Ok(store)
+    }
+
+    /// Preloads named lists not already present (e.g. from a config file
+    /// read at startup), each seeded with `titles` in order and written
+    /// through to storage so a later restart finds them without the config.
+    pub fn seed(&self, lists: HashMap<String, Vec<String>>) -> anyhow::Result<()> {
+        let mut locked = self.lists.write().unwrap();
+        for (name, titles) in lists {
+            if locked.contains_key(&name) {
+                continue;
+            }
+            self.storage.create_list(&name)?;
+            self.storage.replace_items(&name, &titles)?;
+            locked.insert(name, Entry::from_titles(titles));
+        }
+        Ok(())
+    }
+}
+
+/// Serves the CRUD API described in this module at `addr` until the process
+/// is killed.
+pub async fn serve(addr: SocketAddr, store: ListStore) {
+    let app = router(store);
+
+    tracing::debug!("listening on {}", addr);
+
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
+
+/// One documented REST route: enough to both register it on the real
+/// `Router` (via [`router`]) and describe it in an OpenAPI `paths` entry
+/// (via `bin/schemas.rs`), so adding an entry to [`routes`] is all a new
+/// handler needs to show up in both places.
+pub struct RouteSpec {
+    pub path: &'static str,
+    pub method: &'static str,
+    pub summary: &'static str,
+    pub content_type: &'static str,
+    pub request: Option<(String, RootSchema)>,
+    pub response: Option<(String, RootSchema)>,
+    pub statuses: &'static [u16],
+    handler: MethodRouter<ListStore>,
+}
+
+fn named_schema<T: JsonSchema>() -> (String, RootSchema) {
+    (
+        T::schema_name(),
+        schemars::gen::SchemaGenerator::default().into_root_schema_for::<T>(),
+    )
+}
+
+pub fn routes() -> Vec<RouteSpec> {
+    vec![
+        RouteSpec {
+            path: "/lists",
+            method: "post",
+            summary: "Create a new, empty named list",
+            content_type: "application/json",
+            request: Some(named_schema::<CreateList>()),
+            response: Some(named_schema::<ListResponse>()),
+            statuses: &[201, 409],
+            handler: post(create_list),
+        },
+        RouteSpec {
+            path: "/lists/:name",
+            method: "get",
+            summary: "Fetch a named list and its current items",
+            content_type: "application/json",
+            request: None,
+            response: Some(named_schema::<ListResponse>()),
+            statuses: &[200, 404],
+            handler: get(get_list),
+        },
+        RouteSpec {
+            path: "/lists/:name",
+            method: "delete",
+            summary: "Delete a named list",
+            content_type: "application/json",
+            request: None,
+            response: None,
+            statuses: &[204, 404],
+            handler: delete(delete_list),
+        },
+        RouteSpec {
+            path: "/lists/:name/items",
+            method: "post",
+            summary: "Append a new item to a named list",
+            content_type: "application/json",
+            request: Some(named_schema::<AddItem>()),
+            response: Some(named_schema::<ListItem>()),
+            statuses: &[201, 404],
+            handler: post(add_item),
+        },
+        RouteSpec {
+            path: "/lists/:name/items/:index",
+            method: "delete",
+            summary: "Remove an item from a named list by its position",
+            content_type: "application/json",
+            request: None,
+            response: None,
+            statuses: &[204, 404],
+            handler: delete(remove_item),
+        },
+        RouteSpec {
+            path: "/lists/:name/events",
+            method: "get",
+            summary: "Subscribe to live mutations on a named list",
+            content_type: "text/event-stream",
+            request: None,
+            response: Some(named_schema::<ListEvent>()),
+            statuses: &[200, 404],
+            handler: get(list_events),
+        },
+    ]
+}
+
+/// Builds the real `Router` from [`routes`]: entries that share a path are
+/// merged into one `MethodRouter` so `GET`/`DELETE` on `/lists/:name` (for
+/// example) still register as a single route.
+pub fn router(store: ListStore) -> Router {
+    let mut by_path: Vec<(&'static str, MethodRouter<ListStore>)> = Vec::new();
+    for spec in routes() {
+        match by_path.iter_mut().find(|(path, _)| *path == spec.path) {
+            Some((_, existing)) => {
+                *existing = std::mem::replace(existing, MethodRouter::new()).merge(spec.handler);
+            }
+            None => by_path.push((spec.path, spec.handler)),
+        }
+    }
+
+    by_path
+        .into_iter()
+        .fold(Router::new(), |router, (path, method_router)| router.route(path, method_router))
+        .with_state(store)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ListResponse {
+    name: String,
+    items: Vec<ListItem>,
+}
+
+impl ListResponse {
+    fn new(name: String, list: &List) -> Self {
+        Self {
+            name,
+            items: list.items().cloned().collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct CreateList {
+    name: String,
+}
+
+async fn create_list(
+    State(store): State<ListStore>,
+    Json(body): Json<CreateList>,
+) -> Result<(StatusCode, Json<ListResponse>), StatusCode> {
+    let mut lists = store.lists.write().unwrap();
+    if lists.contains_key(&body.name) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    store
+        .storage
+        .create_list(&body.name)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let list = ServerList::new().snapshot();
+    let response = ListResponse::new(body.name.clone(), &list);
+    lists.insert(body.name, Entry::new(list));
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn get_list(
+    State(store): State<ListStore>,
+    Path(name): Path<String>,
+) -> Result<Json<ListResponse>, StatusCode> {
+    let list = {
+        let lists = store.lists.read().unwrap();
+        lists.get(&name).map(|entry| entry.list.clone()).ok_or(StatusCode::NOT_FOUND)?
+    };
+    Ok(Json(ListResponse::new(name, &list)))
+}
+
+async fn delete_list(
+    State(store): State<ListStore>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    let mut lists = store.lists.write().unwrap();
+    let Some(entry) = lists.remove(&name) else {
+        return Ok(StatusCode::NOT_FOUND);
+    };
+    drop(lists);
+
+    store.storage.delete_list(&name).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let _ = entry.events.send(ListEvent::ListCleared);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct AddItem {
+    title: String,
+}
+
+async fn add_item(
+    State(store): State<ListStore>,
+    Path(name): Path<String>,
+    Json(body): Json<AddItem>,
+) -> Result<(StatusCode, Json<ListItem>), StatusCode> {
+    let mut lists = store.lists.write().unwrap();
+    let entry = lists.get_mut(&name).ok_or(StatusCode::NOT_FOUND)?;
+    let item = entry.list.add(body.title);
+    store
+        .storage
+        .replace_items(&name, &titles(&entry.list))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let _ = entry.events.send(ListEvent::ItemAdded { item: item.clone() });
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+async fn remove_item(
+    State(store): State<ListStore>,
+    Path((name, index)): Path<(String, usize)>,
+) -> Result<StatusCode, StatusCode> {
+    let mut lists = store.lists.write().unwrap();
+    let entry = lists.get_mut(&name).ok_or(StatusCode::NOT_FOUND)?;
+    let id = entry
+        .list
+        .items()
+        .nth(index)
+        .map(|item| item.id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    entry.list.remove(id).map_err(|_| StatusCode::NOT_FOUND)?;
+    store
+        .storage
+        .replace_items(&name, &titles(&entry.list))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let _ = entry.events.send(ListEvent::ItemRemoved { id });
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_events(
+    State(store): State<ListStore>,
+    Path(name): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    let receiver = {
+        let lists = store.lists.read().unwrap();
+        lists.get(&name).ok_or(StatusCode::NOT_FOUND)?.events.subscribe()
+    };
+
+    let stream = BroadcastStream::new(receiver).filter_map(|event| async move {
+        let event = event.ok()?;
+        Some(Ok(Event::default()
+            .json_data(event)
+            .expect("ListEvent always serializes to JSON")))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(15))))
+}
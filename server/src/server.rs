@@ -0,0 +1,115 @@
+//! A networked transport around [`ServerList`]: clients connect over
+//! WebSocket and speak the [`SyncMessage`] wire envelope described at the top
+//! of [`crate::lists`]. On connect a client is handed a snapshot of the list
+//! (its current items plus the fork point to build on); pushing
+//! `changes_to_commit()` back runs [`ServerList::commit`] and the confirmed
+//! changes are both returned to the sender and broadcast to every other
+//! connected client, so their local `List`s can `apply_commit` and stay in
+//! sync.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use tokio::sync::broadcast;
+use tokio::sync::Mutex;
+
+use crate::lists::{ServerList, SyncMessage};
+
+/// Confirmed changes fanned out to every connected client, tagged with the
+/// agent id of whichever connection produced them so that connection can
+/// skip its own broadcast (it already received the changes as its direct
+/// reply).
+#[derive(Clone)]
+struct Confirmed {
+    origin: u32,
+    message: SyncMessage,
+}
+
+#[derive(Clone)]
+struct SharedState {
+    list: Arc<Mutex<ServerList>>,
+    confirmed: broadcast::Sender<Confirmed>,
+}
+
+/// Serves the sync protocol at `ws://<addr>/sync` until the process is killed.
+pub async fn serve(addr: SocketAddr) {
+    let (confirmed, _) = broadcast::channel(16);
+    let state = SharedState {
+        list: Arc::new(Mutex::new(ServerList::new())),
+        confirmed,
+    };
+
+    let app = Router::new().route("/sync", get(sync)).with_state(state);
+
+    tracing::debug!("listening on {}", addr);
+
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
+
+async fn sync(ws: WebSocketUpgrade, State(state): State<SharedState>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_connection(socket, state))
+}
+
+async fn handle_connection(mut socket: WebSocket, state: SharedState) {
+    let agent_id = {
+        let mut list = state.list.lock().await;
+        list.snapshot()
+    };
+    if send(&mut socket, &agent_id.to_sync_message()).await.is_err() {
+        return;
+    }
+    let agent_id = agent_id.agent_id();
+
+    let mut confirmed = state.confirmed.subscribe();
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let Some(Ok(Message::Text(text))) = incoming else { break };
+                let Ok(message) = SyncMessage::from_json(&text) else { continue };
+
+                let reply = {
+                    let mut list = state.list.lock().await;
+                    list.commit_message(&message)
+                };
+                let Ok(reply) = reply else { continue };
+
+                if !reply.changes.is_empty() {
+                    let _ = state.confirmed.send(Confirmed {
+                        origin: agent_id,
+                        message: reply.clone(),
+                    });
+                }
+                if send(&mut socket, &reply).await.is_err() {
+                    break;
+                }
+            }
+            broadcast = confirmed.recv() => {
+                match broadcast {
+                    Ok(confirmed) if confirmed.origin != agent_id => {
+                        if send(&mut socket, &confirmed.message).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &SyncMessage) -> Result<(), axum::Error> {
+    let json = message
+        .to_json()
+        .expect("SyncMessage always serializes to JSON");
+    socket.send(Message::Text(json)).await
+}
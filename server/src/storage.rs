@@ -0,0 +1,100 @@
+//! Durable backing store for the REST registry's named lists: a normalized
+//! SQLite `lists`/`items` pair. This only persists each item's `title` (not
+//! the full [`crate::lists::List`] CRDT history `ServerList`/[`crate::store`]
+//! track) — a title is all [`crate::registry::ListStore`] needs to rebuild a
+//! list's items on restart via repeated [`crate::lists::List::add`] calls.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use rusqlite::{params, Connection};
+
+/// Wraps a single SQLite connection behind a `Mutex`, since `Connection`
+/// itself isn't `Sync` and handlers call in from multiple requests at once.
+pub struct Storage {
+    conn: Mutex<Connection>,
+}
+
+impl Storage {
+    /// Opens (creating if needed) a SQLite database file at `path` and
+    /// ensures its schema exists.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// A non-durable store for tests: same schema, backed by SQLite's
+    /// `:memory:` database instead of a file.
+    pub fn in_memory() -> Self {
+        Self::new(Connection::open_in_memory().expect("in-memory sqlite always opens"))
+            .expect("in-memory sqlite always migrates")
+    }
+
+    fn new(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS lists (name TEXT PRIMARY KEY);
+             CREATE TABLE IF NOT EXISTS items (
+                 list_name TEXT NOT NULL,
+                 position INTEGER NOT NULL,
+                 value TEXT NOT NULL
+             );",
+        )?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Every persisted list, in no particular order, each with its items in
+    /// `position` order — what `ListStore::open` replays into fresh `List`s
+    /// at startup.
+    pub fn load_all(&self) -> Result<Vec<(String, Vec<String>)>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut names_stmt = conn.prepare("SELECT name FROM lists")?;
+        let names = names_stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut items_stmt = conn.prepare("SELECT value FROM items WHERE list_name = ?1 ORDER BY position")?;
+        names
+            .into_iter()
+            .map(|name| {
+                let items = items_stmt
+                    .query_map(params![name], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                Ok((name, items))
+            })
+            .collect()
+    }
+
+    pub fn create_list(&self, name: &str) -> Result<()> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("INSERT INTO lists (name) VALUES (?1)", params![name])?;
+        Ok(())
+    }
+
+    pub fn delete_list(&self, name: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM items WHERE list_name = ?1", params![name])?;
+        conn.execute("DELETE FROM lists WHERE name = ?1", params![name])?;
+        Ok(())
+    }
+
+    /// Overwrites `name`'s persisted items with `titles`, in order. Called
+    /// after every in-memory add/remove so storage always mirrors the live
+    /// list rather than tracking each mutation incrementally — simple, and
+    /// correct regardless of how items were reordered or tombstoned.
+    pub fn replace_items(&self, name: &str, titles: &[String]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM items WHERE list_name = ?1", params![name])?;
+        for (position, value) in titles.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO items (list_name, position, value) VALUES (?1, ?2, ?3)",
+                params![name, position as i64, value],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
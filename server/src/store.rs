@@ -0,0 +1,176 @@
+//! Durable storage for a [`ServerList`](crate::lists::ServerList)'s committed
+//! history: an append-only log of confirmed changes, plus enough of a
+//! checkpoint to mint fresh ids that don't collide with history after a
+//! restart.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::lists::Change;
+
+/// A durable log of confirmed [`Change`]s. `ServerList::commit` appends to
+/// one on every successful commit; `ServerList::open` replays one to rebuild
+/// state after a restart. `Send` so a `ServerList` (and its store) can live
+/// behind the `Arc<Mutex<_>>` in [`crate::server`].
+pub trait ChangeStore: Send {
+    fn append(&mut self, changes: &[Change]) -> Result<()>;
+    fn load(&self) -> Result<Vec<Change>>;
+}
+
+/// The default, non-durable store behind `ServerList::new()`: commits are
+/// accepted but nothing is kept, so a fresh `ServerList` always starts empty.
+#[derive(Debug)]
+pub struct NullChangeStore;
+
+impl ChangeStore for NullChangeStore {
+    fn append(&mut self, _changes: &[Change]) -> Result<()> {
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<Change>> {
+        Ok(vec![])
+    }
+}
+
+/// An append-only, file-backed [`ChangeStore`]. Each change is written as a
+/// length-prefixed JSON record so [`load`](ChangeStore::load) can stream them
+/// back in commit order; a sidecar `.checkpoint` file tracks the highest
+/// agent/item id seen so far, rewritten on every append.
+#[derive(Debug)]
+pub struct FileChangeStore {
+    path: PathBuf,
+}
+
+impl FileChangeStore {
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn checkpoint_path(&self) -> PathBuf {
+        let mut path = self.path.clone();
+        path.set_extension("checkpoint");
+        path
+    }
+
+    fn read_checkpoint(&self) -> Checkpoint {
+        std::fs::read(self.checkpoint_path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_checkpoint(&self, checkpoint: &Checkpoint) -> Result<()> {
+        std::fs::write(self.checkpoint_path(), serde_json::to_vec(checkpoint)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Checkpoint {
+    max_agent_id: u32,
+    max_item_id: u32,
+}
+
+impl Checkpoint {
+    fn absorb(&mut self, change: &Change) {
+        if let Some(id) = change.operation().item_id() {
+            self.max_agent_id = self.max_agent_id.max(id.agent());
+            self.max_item_id = self.max_item_id.max(id.id());
+        }
+    }
+}
+
+impl ChangeStore for FileChangeStore {
+    fn append(&mut self, changes: &[Change]) -> Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        for change in changes {
+            let bytes = serde_json::to_vec(change)?;
+            file.write_all(&(bytes.len() as u32).to_le_bytes())?;
+            file.write_all(&bytes)?;
+        }
+        file.flush()?;
+
+        let mut checkpoint = self.read_checkpoint();
+        for change in changes {
+            checkpoint.absorb(change);
+        }
+        self.write_checkpoint(&checkpoint)?;
+
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<Change>> {
+        read_records(&self.path)
+    }
+}
+
+fn read_records(path: &Path) -> Result<Vec<Change>> {
+    let Ok(file) = File::open(path) else {
+        return Ok(vec![]);
+    };
+    let mut reader = BufReader::new(file);
+    let mut changes = Vec::new();
+    loop {
+        let mut len_bytes = [0u8; 4];
+        match reader.read_exact(&mut len_bytes) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let mut bytes = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+        reader.read_exact(&mut bytes)?;
+        changes.push(serde_json::from_slice(&bytes)?);
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A real, publicly-constructible change (a fresh snapshot's own root)
+    /// to exercise the store with, since `Change`'s constructors are private
+    /// to `lists`.
+    fn sample_change() -> Change {
+        crate::lists::ServerList::new()
+            .snapshot()
+            .changes_to_commit()
+            .into_iter()
+            .next()
+            .unwrap()
+    }
+
+    #[test]
+    fn load_before_any_append_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileChangeStore::open(dir.path().join("changes.log"));
+
+        assert_eq!(store.load().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn appended_changes_round_trip_through_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = FileChangeStore::open(dir.path().join("changes.log"));
+
+        let change = sample_change();
+        store.append(&[change.clone()]).unwrap();
+
+        assert_eq!(store.load().unwrap(), vec![change]);
+    }
+
+    #[test]
+    fn append_is_cumulative_across_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = FileChangeStore::open(dir.path().join("changes.log"));
+
+        store.append(&[sample_change()]).unwrap();
+        store.append(&[sample_change()]).unwrap();
+
+        assert_eq!(store.load().unwrap().len(), 2);
+    }
+}